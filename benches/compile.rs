@@ -0,0 +1,88 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+const HELP: &str = include_str!("../examples/help.txt");
+const INDEXED: &str = include_str!("../examples/indexed.txt");
+
+/// A log like source, with many short elements, nested ones and escapes in each line.
+fn synthetic_log(lines: usize) -> String {
+    const LEVELS: [(&str, &str); 4] = [
+        ("TRACE", "gray"),
+        ("DEBUG", "blue"),
+        ("INFO", "green"),
+        ("WARN", "yellow,b"),
+    ];
+
+    (0..lines)
+        .map(|i| {
+            let (level, color) = LEVELS[i % LEVELS.len()];
+            format!(
+                "<gray 12:{:02}:{:02}.{:03}> <{color} {level:5}> <b,i server::conn>: request \
+                 \\<{i}\\> done in <yellow {}ms>, <u <bg:66ccff,black client> <b {}>>",
+                i / 60 % 60,
+                i % 60,
+                i % 1000,
+                i % 97,
+                i % 13,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+    vec![
+        ("help", HELP.to_owned()),
+        ("indexed", INDEXED.to_owned()),
+        ("log", synthetic_log(10000)),
+    ]
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, s| {
+            b.iter(|| tui_markup::parser::parse(black_box(s)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+#[cfg(feature = "ansi")]
+fn compile_ansi(c: &mut Criterion) {
+    use tui_markup::generator::ANSIStringsGenerator;
+
+    let mut group = c.benchmark_group("compile/ansi");
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, s| {
+            b.iter(|| tui_markup::compile::<ANSIStringsGenerator>(black_box(s)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "ansi"))]
+fn compile_ansi(_: &mut Criterion) {}
+
+#[cfg(feature = "ratatui")]
+fn compile_ratatui(c: &mut Criterion) {
+    use tui_markup::generator::RatatuiTextGenerator;
+
+    let mut group = c.benchmark_group("compile/ratatui");
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, s| {
+            b.iter(|| tui_markup::compile::<RatatuiTextGenerator>(black_box(s)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "ratatui"))]
+fn compile_ratatui(_: &mut Criterion) {}
+
+criterion_group!(benches, parse, compile_ansi, compile_ratatui);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+use tui_markup::LocatedError;
+
+/// Error location must point to a char of the source, so it can be shown to user.
+pub fn check_location(source: &str, error: &impl LocatedError) {
+    let (line, column) = error.location();
+    let text = source
+        .lines()
+        .nth(line - 1)
+        .unwrap_or_else(|| panic!("error line {line} is out of source"));
+    assert!(
+        column >= 1 && column - 1 < text.len() && text.is_char_boundary(column - 1),
+        "error column {column} is out of line {text:?}"
+    );
+}
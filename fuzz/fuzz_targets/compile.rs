@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tui_markup::{
+    compile, compile_with,
+    generator::{
+        ANSIStringsGenerator, CrosstermCommandsGenerator, CursiveStyledStringGenerator,
+        NuAnsiTermGenerator, RatatuiTextGenerator,
+    },
+};
+
+mod common;
+
+fuzz_target!(|source: &str| {
+    match compile::<ANSIStringsGenerator>(source) {
+        Ok(text) => {
+            let _ = text.to_string();
+            let _ = text.minimal_sgr().to_string();
+        }
+        Err(e) => {
+            common::check_location(source, &e);
+            // All generators share the parser, so they fail in the same way
+            return;
+        }
+    }
+
+    let _ = compile_with(source, <ANSIStringsGenerator>::default().merge_spans(true)).unwrap();
+    let _ = compile::<RatatuiTextGenerator>(source).unwrap();
+    let _ = compile_with(source, <RatatuiTextGenerator>::default().merge_spans(true)).unwrap();
+    let _ = compile::<CrosstermCommandsGenerator>(source).unwrap();
+    let _ = compile::<CursiveStyledStringGenerator>(source).unwrap();
+    let _ = compile::<NuAnsiTermGenerator>(source).unwrap();
+});
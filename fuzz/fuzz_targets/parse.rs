@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tui_markup::{parser::parse, printer::print};
+
+mod common;
+
+fuzz_target!(|source: &str| {
+    match parse(source) {
+        Ok(ast) => {
+            let printed = print(&ast);
+            assert!(
+                parse(&printed).is_ok(),
+                "printed AST is invalid: {printed:?}"
+            );
+        }
+        Err(e) => common::check_location(source, &e),
+    }
+});
@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use tui_markup::{
+    __private::anstyle::{Ansi256Color, AnsiColor, Color, Effects, RgbColor, Style},
+    generator::ANSIStringsGenerator,
+};
+
+use crate::strict::compile_strict;
+
+const EFFECTS: [(Effects, &str); 12] = [
+    (Effects::BOLD, "BOLD"),
+    (Effects::DIMMED, "DIMMED"),
+    (Effects::ITALIC, "ITALIC"),
+    (Effects::UNDERLINE, "UNDERLINE"),
+    (Effects::DOUBLE_UNDERLINE, "DOUBLE_UNDERLINE"),
+    (Effects::CURLY_UNDERLINE, "CURLY_UNDERLINE"),
+    (Effects::DOTTED_UNDERLINE, "DOTTED_UNDERLINE"),
+    (Effects::DASHED_UNDERLINE, "DASHED_UNDERLINE"),
+    (Effects::BLINK, "BLINK"),
+    (Effects::INVERT, "INVERT"),
+    (Effects::HIDDEN, "HIDDEN"),
+    (Effects::STRIKETHROUGH, "STRIKETHROUGH"),
+];
+
+fn ansi_color(c: AnsiColor) -> &'static str {
+    match c {
+        AnsiColor::Black => "Black",
+        AnsiColor::Red => "Red",
+        AnsiColor::Green => "Green",
+        AnsiColor::Yellow => "Yellow",
+        AnsiColor::Blue => "Blue",
+        AnsiColor::Magenta => "Magenta",
+        AnsiColor::Cyan => "Cyan",
+        AnsiColor::White => "White",
+        AnsiColor::BrightBlack => "BrightBlack",
+        AnsiColor::BrightRed => "BrightRed",
+        AnsiColor::BrightGreen => "BrightGreen",
+        AnsiColor::BrightYellow => "BrightYellow",
+        AnsiColor::BrightBlue => "BrightBlue",
+        AnsiColor::BrightMagenta => "BrightMagenta",
+        AnsiColor::BrightCyan => "BrightCyan",
+        AnsiColor::BrightWhite => "BrightWhite",
+    }
+}
+
+fn color(c: Option<Color>) -> TokenStream {
+    let c = match c {
+        None => return quote!(::core::option::Option::None),
+        Some(Color::Ansi(c)) => {
+            let variant = syn::Ident::new(ansi_color(c), proc_macro2::Span::call_site());
+            quote!(::tui_markup::__private::anstyle::Color::Ansi(
+                ::tui_markup::__private::anstyle::AnsiColor::#variant
+            ))
+        }
+        Some(Color::Ansi256(Ansi256Color(i))) => quote!(
+            ::tui_markup::__private::anstyle::Color::Ansi256(
+                ::tui_markup::__private::anstyle::Ansi256Color(#i)
+            )
+        ),
+        Some(Color::Rgb(RgbColor(r, g, b))) => quote!(
+            ::tui_markup::__private::anstyle::Color::Rgb(
+                ::tui_markup::__private::anstyle::RgbColor(#r, #g, #b)
+            )
+        ),
+    };
+    quote!(::core::option::Option::Some(#c))
+}
+
+fn style(s: &Style) -> TokenStream {
+    let fg = color(s.get_fg_color());
+    let bg = color(s.get_bg_color());
+    let underline = color(s.get_underline_color());
+    let effects = EFFECTS
+        .iter()
+        .filter(|(e, _)| s.get_effects().contains(*e))
+        .map(|(_, name)| syn::Ident::new(name, proc_macro2::Span::call_site()));
+
+    quote!(
+        ::tui_markup::__private::anstyle::Style::new()
+            .fg_color(#fg)
+            .bg_color(#bg)
+            .underline_color(#underline)
+            .effects(::tui_markup::__private::anstyle::Effects::new()
+                #(.insert(::tui_markup::__private::anstyle::Effects::#effects))*)
+    )
+}
+
+pub(crate) fn expand(source: &str) -> Result<TokenStream, String> {
+    let text = compile_strict(source, <ANSIStringsGenerator>::default())?;
+
+    let spans = text.spans().iter().map(|span| {
+        let content = span.text();
+        let style = style(span.style());
+        quote!(::tui_markup::generator::ansi::StyledSpan::new(#style, #content))
+    });
+
+    Ok(quote!(::tui_markup::generator::ansi::StyledText::new(
+        ::std::vec![#(#spans),*]
+    )))
+}
@@ -0,0 +1,69 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use tui_markup::{
+    __private::crossterm::style::{Attribute, Color, ContentStyle},
+    generator::{CrosstermCommandsGenerator, crossterm::Span},
+};
+
+use crate::strict::compile_strict;
+
+fn color(c: Option<Color>) -> TokenStream {
+    match c {
+        None => quote!(::core::option::Option::None),
+        Some(c) => {
+            // Debug output of every variant is also a valid expression after the enum path.
+            let variant: TokenStream = format!("{c:?}")
+                .parse()
+                .expect("debug of color is valid tokens");
+            quote!(::core::option::Option::Some(
+                ::tui_markup::__private::crossterm::style::Color::#variant
+            ))
+        }
+    }
+}
+
+fn style(s: &ContentStyle) -> TokenStream {
+    let fg = color(s.foreground_color);
+    let bg = color(s.background_color);
+    let underline = color(s.underline_color);
+    let attributes = Attribute::iterator()
+        .filter(|a| s.attributes.has(*a))
+        .map(|a| format_ident!("{a:?}"));
+
+    quote!(::tui_markup::__private::crossterm::style::ContentStyle {
+        foreground_color: #fg,
+        background_color: #bg,
+        underline_color: #underline,
+        attributes: ::tui_markup::__private::crossterm::style::Attributes::none()
+            #(.with(::tui_markup::__private::crossterm::style::Attribute::#attributes))*,
+    })
+}
+
+pub(crate) fn expand(source: &str) -> Result<TokenStream, String> {
+    let spans = compile_strict(source, <CrosstermCommandsGenerator>::default())?;
+
+    let spans = spans.iter().map(|span| match span {
+        Span::NoStyle(p) => {
+            let text = p.0.as_ref();
+            quote!(::tui_markup::generator::crossterm::Span::NoStyle(
+                ::tui_markup::__private::crossterm::style::Print(
+                    ::std::borrow::Cow::Borrowed(#text)
+                )
+            ))
+        }
+        Span::Styled(p) => {
+            let text = p.0.content().as_ref();
+            let style = style(p.0.style());
+            quote!(::tui_markup::generator::crossterm::Span::Styled(
+                ::tui_markup::__private::crossterm::style::PrintStyledContent(
+                    ::tui_markup::__private::crossterm::style::StyledContent::new(
+                        #style,
+                        ::std::borrow::Cow::Borrowed(#text),
+                    )
+                )
+            ))
+        }
+    });
+
+    Ok(quote!(::std::vec![#(#spans),*]))
+}
@@ -0,0 +1,127 @@
+#![forbid(unsafe_code)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+//! # tui markup macros
+//!
+//! Compile [tui-markup] source at compile time.
+//!
+//! The [`markup!`] macro parses markup source and checks every tag against tag convertor of the
+//! chosen generator when your crate is compiled. Syntax errors and unknown tags become compile
+//! errors at the macro call site, and the expansion builds output directly, without parsing at
+//! runtime. It's not a constant, the output is still allocated by `vec!` each time the expansion
+//! is evaluated.
+//!
+//! ## Generators
+//!
+//! Generators are under feature gates, the same as [tui-markup]. Generated code uses types through
+//! `tui_markup`, so your crate needs to depend on it with the same feature enabled.
+//!
+//! | feature     | generator name | output type                                      |
+//! | :---------- | :------------- | :----------------------------------------------- |
+//! | `ansi`      | `ansi`         | `tui_markup::generator::ansi::StyledText<'static>` |
+//! | `ratatui`   | `ratatui`      | `ratatui::text::Text<'static>`                   |
+//! | `crossterm` | `crossterm`    | `Vec<tui_markup::generator::crossterm::Span<'static>>` |
+//!
+//! [tui-markup]: https://docs.rs/tui-markup
+
+#[cfg(feature = "ansi")]
+mod ansi;
+#[cfg(feature = "crossterm")]
+mod crossterm;
+#[cfg(feature = "ratatui")]
+mod ratatui;
+#[cfg(any(feature = "ansi", feature = "ratatui", feature = "crossterm"))]
+mod strict;
+
+use proc_macro::TokenStream;
+use syn::{
+    Ident, LitStr, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+};
+
+struct Input {
+    generator: Ident,
+    source: LitStr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let generator = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let source = input.parse()?;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self { generator, source })
+    }
+}
+
+// Without any generator feature, every input is an unknown generator error.
+#[cfg_attr(
+    not(any(feature = "ansi", feature = "ratatui", feature = "crossterm")),
+    allow(unreachable_code, unused_variables)
+)]
+fn expand(input: &Input) -> syn::Result<proc_macro2::TokenStream> {
+    let expand: fn(&str) -> Result<proc_macro2::TokenStream, String> =
+        match input.generator.to_string().as_str() {
+            #[cfg(feature = "ansi")]
+            "ansi" => ansi::expand,
+            #[cfg(feature = "ratatui")]
+            "ratatui" => ratatui::expand,
+            #[cfg(feature = "crossterm")]
+            "crossterm" => crossterm::expand,
+            name => {
+                let enabled: &[&str] = &[
+                    #[cfg(feature = "ansi")]
+                    "ansi",
+                    #[cfg(feature = "ratatui")]
+                    "ratatui",
+                    #[cfg(feature = "crossterm")]
+                    "crossterm",
+                ];
+                return Err(syn::Error::new(
+                    input.generator.span(),
+                    format!(
+                        "unknown generator `{name}`, enabled generators: [{}]",
+                        enabled.join(", ")
+                    ),
+                ));
+            }
+        };
+
+    expand(&input.source.value()).map_err(|msg| syn::Error::new(input.source.span(), msg))
+}
+
+/// Compile markup source into output of a builtin generator at compile time.
+///
+/// The first argument is generator name, see [crate document](crate) for available ones. The
+/// second is markup source, which must be a string literal.
+///
+/// Unlike `tui_markup::compile`, which ignores tags the generator does not understand, unknown tags
+/// are errors here.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ansi")] {
+/// use tui_markup_macros::markup;
+///
+/// let text = markup!(ansi, "<green,b hello> world");
+/// assert_eq!(text.to_string(), "\x1b[1m\x1b[32mhello\x1b[0m world");
+/// # }
+/// ```
+///
+/// Invalid markup does not compile:
+///
+/// ```compile_fail
+/// use tui_markup_macros::markup;
+///
+/// let text = markup!(ansi, "<b hello");
+/// ```
+#[proc_macro]
+pub fn markup(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,84 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use tui_markup::{
+    __private::ratatui_core::style::{Color, Style},
+    generator::RatatuiTextGenerator,
+};
+
+use crate::strict::compile_strict;
+
+fn color(c: Color) -> TokenStream {
+    let name = match c {
+        Color::Rgb(r, g, b) => {
+            return quote!(::tui_markup::__private::ratatui_core::style::Color::Rgb(#r, #g, #b));
+        }
+        Color::Indexed(i) => {
+            return quote!(::tui_markup::__private::ratatui_core::style::Color::Indexed(#i));
+        }
+        Color::Reset => "Reset",
+        Color::Black => "Black",
+        Color::Red => "Red",
+        Color::Green => "Green",
+        Color::Yellow => "Yellow",
+        Color::Blue => "Blue",
+        Color::Magenta => "Magenta",
+        Color::Cyan => "Cyan",
+        Color::Gray => "Gray",
+        Color::DarkGray => "DarkGray",
+        Color::LightRed => "LightRed",
+        Color::LightGreen => "LightGreen",
+        Color::LightYellow => "LightYellow",
+        Color::LightBlue => "LightBlue",
+        Color::LightMagenta => "LightMagenta",
+        Color::LightCyan => "LightCyan",
+        Color::White => "White",
+    };
+    let variant = syn::Ident::new(name, proc_macro2::Span::call_site());
+    quote!(::tui_markup::__private::ratatui_core::style::Color::#variant)
+}
+
+fn style(s: Style) -> TokenStream {
+    let mut tokens = quote!(::tui_markup::__private::ratatui_core::style::Style::new());
+    if let Some(c) = s.fg.map(color) {
+        tokens.extend(quote!(.fg(#c)));
+    }
+    if let Some(c) = s.bg.map(color) {
+        tokens.extend(quote!(.bg(#c)));
+    }
+    if !s.add_modifier.is_empty() {
+        let bits = s.add_modifier.bits();
+        tokens.extend(quote!(.add_modifier(
+            ::tui_markup::__private::ratatui_core::style::Modifier::from_bits_retain(#bits)
+        )));
+    }
+    if !s.sub_modifier.is_empty() {
+        let bits = s.sub_modifier.bits();
+        tokens.extend(quote!(.remove_modifier(
+            ::tui_markup::__private::ratatui_core::style::Modifier::from_bits_retain(#bits)
+        )));
+    }
+    tokens
+}
+
+pub(crate) fn expand(source: &str) -> Result<TokenStream, String> {
+    let text = compile_strict(source, <RatatuiTextGenerator>::default())?;
+
+    let lines = text.lines.iter().map(|line| {
+        let spans = line.spans.iter().map(|span| {
+            let content = span.content.as_ref();
+            let style = style(span.style);
+            quote!(::tui_markup::__private::ratatui_core::text::Span::styled(#content, #style))
+        });
+        let style = style(line.style);
+        quote!(
+            ::tui_markup::__private::ratatui_core::text::Line::from(::std::vec![#(#spans),*])
+                .style(#style)
+        )
+    });
+    let style = style(text.style);
+
+    Ok(quote!(
+        ::tui_markup::__private::ratatui_core::text::Text::from(::std::vec![#(#lines),*])
+            .style(#style)
+    ))
+}
@@ -0,0 +1,82 @@
+use tui_markup::{
+    Generator,
+    generator::TagConvertor,
+    parser::{self, Visit},
+};
+
+/// Line and column of a slice of source, both starts from 1.
+fn location(source: &str, s: &str) -> (usize, usize) {
+    let offset = s.as_ptr() as usize - source.as_ptr() as usize;
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Visitor which finds the first tag unknown to a convertor.
+struct Checker<'s, 'a, C> {
+    source: &'a str,
+    convertor: &'s mut C,
+    error: Option<String>,
+}
+
+impl<'a, C: TagConvertor<'a>> Visit<'a, &'a str> for Checker<'_, 'a, C> {
+    fn enter_element(&mut self, tags: &[&'a str], _stack: &[&[&'a str]]) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(tag) = tags
+            .iter()
+            .find(|tag| self.convertor.convert_tag(tag).is_none())
+        {
+            let (line, column) = location(self.source, tag);
+            self.error = Some(format!("unknown tag `{tag}` near {line}:{column}"));
+        }
+    }
+}
+
+/// Compile source with a generator, all tags must be known by its convertor.
+pub(crate) fn compile_strict<'a, G: Generator<'a>>(
+    source: &'a str, mut generator: G,
+) -> Result<G::Output, String> {
+    let ast = parser::parse(source).map_err(|e| format!("invalid markup: {e}"))?;
+    let mut checker = Checker {
+        source,
+        convertor: generator.convertor(),
+        error: None,
+    };
+    for line in &ast {
+        parser::visit(line, &mut checker);
+    }
+    if let Some(error) = checker.error {
+        return Err(error);
+    }
+    let ir = generator.convertor().convert_ast(ast);
+    generator
+        .generate(ir)
+        .map_err(|e| format!("generate failed: {e}"))
+}
+
+#[cfg(all(test, feature = "ansi"))]
+mod test {
+    use tui_markup::generator::ANSIStringsGenerator;
+
+    use super::compile_strict;
+
+    fn error(source: &str) -> String {
+        compile_strict(source, <ANSIStringsGenerator>::default()).unwrap_err()
+    }
+
+    #[test]
+    fn test_unknown_tag() {
+        assert_eq!(error("ok\n<b <b,what x>>"), "unknown tag `what` near 2:7");
+        assert_eq!(error("中<fg:blue,no x>"), "unknown tag `no` near 1:11");
+    }
+
+    #[test]
+    fn test_invalid_syntax() {
+        assert!(error("<b x").starts_with("invalid markup: "));
+    }
+}
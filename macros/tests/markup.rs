@@ -0,0 +1,72 @@
+#![allow(unused_macros, unused_imports)]
+
+use tui_markup::{
+    compile,
+    generator::{ANSIStringsGenerator, CrosstermCommandsGenerator, RatatuiTextGenerator},
+};
+use tui_markup_macros::markup;
+
+macro_rules! same_as_runtime {
+    ($($source:literal),* $(,)?) => {
+        #[cfg(feature = "ansi")]
+        #[test]
+        fn test_ansi() {
+            $(assert_eq!(
+                markup!(ansi, $source).to_string(),
+                compile::<ANSIStringsGenerator>($source).unwrap().to_string(),
+            );)*
+        }
+
+        #[cfg(feature = "ratatui")]
+        #[test]
+        fn test_ratatui() {
+            $(assert_eq!(
+                markup!(ratatui, $source),
+                compile::<RatatuiTextGenerator>($source).unwrap(),
+            );)*
+        }
+
+        #[cfg(feature = "crossterm")]
+        #[test]
+        fn test_crossterm() {
+            use tui_markup::__private::crossterm::Command;
+
+            fn render(spans: &[tui_markup::generator::crossterm::Span<'_>]) -> String {
+                let mut result = String::new();
+                for span in spans {
+                    span.write_ansi(&mut result).unwrap();
+                }
+                result
+            }
+
+            $(assert_eq!(
+                render(&markup!(crossterm, $source)),
+                render(&compile::<CrosstermCommandsGenerator>($source).unwrap()),
+            );)*
+        }
+    };
+}
+
+same_as_runtime! {
+    "",
+    "plain \\<text\\>",
+    "<b,i,u,d,s,r,h bold>",
+    "<green fg> <bg:blue bg> <bg:102030,fg:66ccff rgb> <bg:200 indexed>",
+    "<red one <b two>>\nline <yellow three>",
+    "<black a><red b><green c><yellow d><blue e><magenta f><cyan g><white h>",
+}
+
+#[cfg(feature = "ratatui")]
+#[test]
+fn test_ratatui_colors() {
+    assert_eq!(
+        markup!(
+            ratatui,
+            "<gray a><gray+ b><red- c><green- d><yellow- e><blue- f><cyan- g>"
+        ),
+        compile::<RatatuiTextGenerator>(
+            "<gray a><gray+ b><red- c><green- d><yellow- e><blue- f><cyan- g>"
+        )
+        .unwrap(),
+    );
+}
@@ -0,0 +1,251 @@
+//! Cache of compiled output, for render the same markup source repeatedly.
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{Error, Generator, compile_with, generator::helper::IntoOwned};
+
+/// Hit and miss count of a [`CompileCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Count of compile calls which returned a cached output.
+    pub hits: u64,
+    /// Count of compile calls which compiled the source, including failed ones.
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Ratio of hits in all compile calls, 0 if there is no call.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+struct Entry<O> {
+    output: Arc<O>,
+    last_used: u64,
+}
+
+/// A bounded cache around [`compile_with`], keyed by markup source.
+///
+/// Apps which redraw every frame, like ratatui ones, often compile the same markup again and
+/// again. This cache keeps owned outputs of recent sources, so those calls become a hash lookup
+/// and a [`Arc`] clone.
+///
+/// ## One cache, one configuration
+///
+/// Entries are keyed by markup source only, not by the generator. The cache owns the generator,
+/// so all cached outputs are generated with the same configuration, like custom tags or
+/// [`Sanitize`][crate::generator::helper::Sanitize] policy. Use one cache for each configuration
+/// if you have many, never share outputs across them. Changing the generator by
+/// [`generator_mut`][CompileCache::generator_mut] clears all cached outputs.
+///
+/// ## Capacity
+///
+/// When the cache is full, the least recently used entry is evicted. Eviction scans all entries,
+/// so the capacity should be around count of distinct sources in a frame, not thousands.
+///
+/// Outputs must implement [`IntoOwned`], which is provided for generators of `ansi`, `ratatui`,
+/// `crossterm`, `cursive` and `nu-ansi-term` feature. The `console` generator is not supported,
+/// because `console::StyledObject` does not expose it's style and text to be copied. Type
+/// parameter `O` is the owned output type, like `Text<'static>` for
+/// [`RatatuiTextGenerator`][crate::generator::RatatuiTextGenerator].
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ratatui")] {
+/// use tui_markup::{cache::CompileCache, generator::RatatuiTextGenerator};
+///
+/// let mut cache = CompileCache::new(<RatatuiTextGenerator>::default(), 16);
+///
+/// for _frame in 0..3 {
+///     let status = format!("<green {}> files", 42);
+///     let text = cache.compile(&status).unwrap();
+///     assert_eq!(text.lines[0].spans[0].content, "42");
+/// }
+///
+/// assert_eq!(cache.stats().hits, 2);
+/// assert_eq!(cache.stats().misses, 1);
+/// # }
+/// ```
+pub struct CompileCache<G, O> {
+    generator: G,
+    capacity: usize,
+    entries: HashMap<String, Entry<O>>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl<G, O> CompileCache<G, O>
+where
+    G: for<'a> Generator<'a>,
+    for<'a> <G as Generator<'a>>::Output: IntoOwned<Owned = O>,
+{
+    /// Create a cache which keeps at most `capacity` outputs.
+    ///
+    /// A capacity of 0 disables caching, every call compiles the source.
+    pub fn new(generator: G, capacity: usize) -> Self {
+        Self {
+            generator,
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            tick: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Get output of markup source, compile it with the generator if not cached.
+    ///
+    /// ## Errors
+    ///
+    /// If source contains invalid syntax or generator failed. Errors are not cached.
+    pub fn compile<'s>(
+        &mut self, s: &'s str,
+    ) -> Result<Arc<O>, Error<'s, <G as Generator<'s>>::Err>> {
+        self.tick += 1;
+
+        if let Some(entry) = self.entries.get_mut(s) {
+            entry.last_used = self.tick;
+            self.stats.hits += 1;
+            return Ok(Arc::clone(&entry.output));
+        }
+
+        self.stats.misses += 1;
+        let output = Arc::new(compile_with(s, &mut self.generator)?.into_owned());
+
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict();
+            }
+            self.entries.insert(s.to_owned(), Entry {
+                output: Arc::clone(&output),
+                last_used: self.tick,
+            });
+        }
+
+        Ok(output)
+    }
+
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(source, _)| source.clone());
+        if let Some(source) = oldest {
+            self.entries.remove(&source);
+        }
+    }
+
+    /// Hit and miss count since creation or last [`reset_stats`][CompileCache::reset_stats].
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Reset hit and miss count to 0.
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Count of cached outputs.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// If there is no cached output.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Max count of cached outputs.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Remove all cached outputs.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Get the generator.
+    #[must_use]
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+
+    /// Get the generator for change it's configuration, this clears all cached outputs.
+    pub fn generator_mut(&mut self) -> &mut G {
+        self.clear();
+        &mut self.generator
+    }
+}
+
+impl<G, O> std::fmt::Debug for CompileCache<G, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompileCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.entries.len())
+            .field("stats", &self.stats)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "ansi"))]
+mod test {
+    use super::CompileCache;
+    use crate::generator::ANSIStringsGenerator;
+
+    #[test]
+    fn test_hit_and_miss() {
+        let mut cache = CompileCache::new(<ANSIStringsGenerator>::default(), 4);
+        let a = cache.compile("<b a>").unwrap();
+        let b = cache.compile("<b a>").unwrap();
+        assert!(std::sync::Arc::ptr_eq(&a, &b));
+        assert_eq!(a.to_string(), "\x1b[1ma\x1b[0m");
+
+        assert!(cache.compile("<b a").is_err());
+        assert_eq!(cache.len(), 1);
+        assert_eq!((cache.stats().hits, cache.stats().misses), (1, 2));
+        assert!((cache.stats().hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let mut cache = CompileCache::new(<ANSIStringsGenerator>::default(), 2);
+        cache.compile("a").unwrap();
+        cache.compile("b").unwrap();
+        cache.compile("a").unwrap();
+        cache.compile("c").unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.reset_stats();
+        cache.compile("a").unwrap();
+        cache.compile("b").unwrap();
+        assert_eq!((cache.stats().hits, cache.stats().misses), (1, 1));
+    }
+
+    #[test]
+    fn test_zero_capacity_and_generator_change() {
+        let mut cache = CompileCache::new(<ANSIStringsGenerator>::default(), 0);
+        cache.compile("a").unwrap();
+        cache.compile("a").unwrap();
+        assert_eq!(cache.stats().hits, 0);
+        assert!(cache.is_empty());
+
+        let mut cache = CompileCache::new(<ANSIStringsGenerator>::default(), 1);
+        cache.compile("a").unwrap();
+        *cache.generator_mut() = <ANSIStringsGenerator>::default().merge_spans(true);
+        assert!(cache.is_empty());
+    }
+}
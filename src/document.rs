@@ -0,0 +1,320 @@
+//! Lazy, line based compilation of large markup source.
+
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    Error, Generator,
+    generator::TagConvertor,
+    parser::{line_at, line_starts, parse_line},
+};
+
+/// A markup source which is compiled line by line on demand.
+///
+/// Creating a document only indexes start of each line. A line is parsed, converted and generated
+/// only when it's requested, and the result is cached. So showing a small window of a huge source,
+/// like a scrolling viewer, only costs the visible lines.
+///
+/// At most [`cache_capacity`][Self::cache_capacity] lines are cached, lines farthest from the
+/// requested ones are evicted first, so memory stays bounded when scrolling through the whole
+/// source.
+///
+/// Each line is generated separately, as if it's the only line of source. Lines are split in the
+/// same way as [`str::lines`], so line index is the same as in [`compile`][crate::compile] result
+/// and error locations.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ratatui")] {
+/// use ratatui::text::Text;
+/// use tui_markup::{document::Document, generator::RatatuiTextGenerator};
+///
+/// let source = (0..10000)
+///     .map(|i| format!("<green {i}>: log"))
+///     .collect::<Vec<_>>()
+///     .join("\n");
+///
+/// let mut doc = Document::new(&source, <RatatuiTextGenerator>::default());
+/// assert_eq!(doc.len(), 10000);
+///
+/// // Only these lines are compiled
+/// let visible = doc.lines(5000..5050).unwrap();
+/// let text = Text::from_iter(visible.into_iter().flat_map(|t| t.lines.clone()));
+/// assert_eq!(text.lines.len(), 50);
+/// assert_eq!(doc.cached_len(), 50);
+/// # }
+/// ```
+pub struct Document<'a, G: Generator<'a>> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+    generator: G,
+    cache: HashMap<usize, G::Output>,
+    cache_capacity: usize,
+}
+
+impl<'a, G: Generator<'a>> Document<'a, G> {
+    /// Default value of [`cache_capacity`][Self::cache_capacity].
+    pub const DEFAULT_CACHE_CAPACITY: usize = 4096;
+
+    /// Create a document by indexing lines of source, nothing is compiled yet.
+    pub fn new(source: &'a str, generator: G) -> Self {
+        Self {
+            source,
+            line_starts: line_starts(source),
+            generator,
+            cache: HashMap::new(),
+            cache_capacity: Self::DEFAULT_CACHE_CAPACITY,
+        }
+    }
+
+    /// Set max count of cached lines, [`DEFAULT_CACHE_CAPACITY`][Self::DEFAULT_CACHE_CAPACITY] by
+    /// default.
+    ///
+    /// Lines of the latest request are always kept, even if there are more than the capacity.
+    #[must_use]
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Whole markup source of this document.
+    #[must_use]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Count of lines.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// If there is no line.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.line_starts.is_empty()
+    }
+
+    /// Markup source of a line, without line ending.
+    #[must_use]
+    pub fn line_source(&self, index: usize) -> Option<&'a str> {
+        line_at(self.source, &self.line_starts, index)
+    }
+
+    fn compile_line(&mut self, index: usize) -> Result<G::Output, Error<'a, G::Err>> {
+        let source = self.line_source(index).unwrap_or_default();
+        let ast = parse_line(index, source)?;
+        let ir = self.generator.convertor().convert_line(ast);
+        self.generator.generate(vec![ir]).map_err(Into::into)
+    }
+
+    fn ensure(&mut self, index: usize) -> Result<(), Error<'a, G::Err>> {
+        if !self.cache.contains_key(&index) {
+            let output = self.compile_line(index)?;
+            self.cache.insert(index, output);
+        }
+        Ok(())
+    }
+
+    /// Evict cached lines farthest from `keep`, until there are no more than the capacity.
+    fn shrink(&mut self, keep: &Range<usize>) {
+        let Some(excess) = self.cache.len().checked_sub(self.cache_capacity) else {
+            return;
+        };
+
+        let mut outside: Vec<usize> = self
+            .cache
+            .keys()
+            .copied()
+            .filter(|index| !keep.contains(index))
+            .collect();
+        outside.sort_unstable_by_key(|&index| {
+            std::cmp::Reverse(if index < keep.start {
+                keep.start - index
+            } else {
+                index - keep.end
+            })
+        });
+        for index in outside.into_iter().take(excess) {
+            self.cache.remove(&index);
+        }
+    }
+
+    /// Get output of a line, compile it if not cached.
+    ///
+    /// Returns `None` if index is out of range.
+    ///
+    /// ## Errors
+    ///
+    /// If the line contains invalid syntax or generator failed. Failed result is not cached.
+    pub fn line(&mut self, index: usize) -> Option<Result<&G::Output, Error<'a, G::Err>>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let result = self.ensure(index);
+        self.shrink(&(index..index + 1));
+        Some(result.map(|()| &self.cache[&index]))
+    }
+
+    /// Get outputs of a range of lines, compile those not cached.
+    ///
+    /// The range is clamped to lines of the document.
+    ///
+    /// ## Errors
+    ///
+    /// If any line in range contains invalid syntax or generator failed. Lines before the failed
+    /// one are still cached.
+    pub fn lines(&mut self, range: Range<usize>) -> Result<Vec<&G::Output>, Error<'a, G::Err>> {
+        let range = range.start.min(self.len())..range.end.min(self.len());
+        let result = range.clone().try_for_each(|index| self.ensure(index));
+        self.shrink(&range);
+        result?;
+        Ok(range.map(|index| &self.cache[&index]).collect())
+    }
+
+    /// Count of cached lines.
+    #[must_use]
+    pub fn cached_len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Remove all cached outputs, for example, after config of the generator is changed.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Remove cached outputs of lines outside of a range, for example, lines scrolled out of view.
+    pub fn evict_outside(&mut self, range: Range<usize>) {
+        self.cache.retain(|index, _| range.contains(index));
+    }
+
+    /// Get the generator.
+    pub fn generator(&mut self) -> &mut G {
+        &mut self.generator
+    }
+}
+
+impl<'a, G: Generator<'a>> std::fmt::Debug for Document<'a, G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Document")
+            .field("lines", &self.len())
+            .field("cached", &self.cached_len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Document;
+    use crate::{
+        generator::{Generator, helper::GeneratorInfallible},
+        parser::ItemC,
+        test_util::Convertor,
+    };
+
+    /// Generator outputs the line count it received, and how many times it's called.
+    struct Counter {
+        convertor: Convertor,
+        called: usize,
+    }
+
+    impl Default for Counter {
+        fn default() -> Self {
+            Self {
+                convertor: Convertor,
+                called: 0,
+            }
+        }
+    }
+
+    impl<'a> Generator<'a> for Counter {
+        type Convertor = Convertor;
+        type Err = GeneratorInfallible;
+        type Output = (usize, usize);
+
+        fn convertor(&mut self) -> &mut Self::Convertor {
+            &mut self.convertor
+        }
+
+        fn generate(
+            &mut self, markup: Vec<Vec<ItemC<'a, Self::Convertor>>>,
+        ) -> Result<Self::Output, Self::Err> {
+            self.called += 1;
+            Ok((markup.len(), self.called))
+        }
+    }
+
+    #[test]
+    fn test_line_index() {
+        for source in ["", "a", "a\n", "a\r\nb\n\nc", "a\r", "\n\n"] {
+            let doc = Document::new(source, Counter::default());
+            let lines: Vec<_> = (0..doc.len())
+                .map(|i| doc.line_source(i).unwrap())
+                .collect();
+            assert_eq!(lines, source.lines().collect::<Vec<_>>(), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn test_cache() {
+        let mut doc = Document::new("a\nb\nc\nd", Counter::default());
+        assert_eq!(doc.line(1).unwrap().unwrap(), &(1, 1));
+        assert_eq!(doc.lines(0..3).unwrap(), vec![&(1, 2), &(1, 1), &(1, 3)]);
+        assert_eq!(doc.lines(3..10).unwrap(), vec![&(1, 4)]);
+        assert!(doc.line(4).is_none());
+        assert_eq!(doc.cached_len(), 4);
+
+        doc.clear_cache();
+        assert_eq!(doc.line(0).unwrap().unwrap(), &(1, 5));
+    }
+
+    #[test]
+    fn test_cache_bounded_when_scrolling() {
+        let source = vec!["line"; 1000].join("\n");
+        let mut doc = Document::new(&source, Counter::default()).cache_capacity(100);
+
+        for start in (0..1000).step_by(10) {
+            assert_eq!(
+                doc.lines(start..start + 50).unwrap().len(),
+                50.min(1000 - start)
+            );
+            assert!(doc.cached_len() <= 100);
+        }
+
+        // Lines near the last request are kept
+        let called = doc.generator().called;
+        doc.lines(950..1000).unwrap();
+        doc.line(900).unwrap().unwrap();
+        assert_eq!(doc.generator().called, called);
+        assert!(doc.line(0).unwrap().is_ok());
+        assert_eq!(doc.generator().called, called + 1);
+        assert_eq!(doc.cached_len(), 100);
+
+        // A request larger than capacity is kept as a whole
+        assert_eq!(doc.lines(0..300).unwrap().len(), 300);
+        assert_eq!(doc.cached_len(), 300);
+        doc.line(0).unwrap().unwrap();
+        assert_eq!(doc.cached_len(), 100);
+    }
+
+    #[test]
+    fn test_evict_outside() {
+        let mut doc = Document::new("a\nb\nc\nd", Counter::default());
+        doc.lines(0..4).unwrap();
+        doc.evict_outside(1..3);
+        assert_eq!(doc.cached_len(), 2);
+        assert_eq!(doc.lines(1..3).unwrap(), vec![&(1, 2), &(1, 3)]);
+    }
+
+    #[test]
+    fn test_error_location() {
+        use crate::error::LocatedError;
+
+        let mut doc = Document::new("ok\n<b x", Counter::default());
+        assert!(doc.line(0).unwrap().is_ok());
+        let error = doc.line(1).unwrap().unwrap_err();
+        assert_eq!(error.location(), (2, 1));
+        assert_eq!(doc.cached_len(), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::{Debug, Display};
+use core::fmt::{Debug, Display};
 
 use crate::parser::Error as ParseError;
 
@@ -25,7 +25,7 @@ impl<GE> Display for Error<'_, GE>
 where
     GE: Display,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::Parse(pe) => f.write_fmt(format_args!("parse failed: {}", pe)),
             Error::Gen(ge) => f.write_fmt(format_args!("generate failed: {}", ge)),
@@ -33,6 +33,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<GE> std::error::Error for Error<'_, GE> where Self: Debug + Display {}
 
 impl<GE: LocatedError> LocatedError for Error<'_, GE> {
@@ -50,9 +51,9 @@ impl<'a, GE> From<ParseError<'a>> for Error<'a, GE> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test {
-    use crate::generator::helper::GeneratorInfallible;
+    use crate::generator::helper::{GeneratorInfallible, WriteError};
 
     #[test]
     fn error_must_impl_std_error() {
@@ -60,6 +61,8 @@ mod test {
 
         is_error::<GeneratorInfallible>();
         is_error::<crate::parser::Error<'_>>();
+        is_error::<crate::generator::DisallowedTag<'_>>();
         is_error::<super::Error<'static, GeneratorInfallible>>();
+        is_error::<super::Error<'static, WriteError<std::io::Error>>>();
     }
 }
@@ -0,0 +1,176 @@
+use std::fmt::{Display, Formatter, Result, Write};
+
+use anstyle::{Effects, Reset, Style};
+
+use super::StyledText;
+use crate::generator::helper::SgrParams;
+
+/// Effects reset by the same SGR code.
+const OFF: [(Effects, u8); 7] = [
+    (Effects::BOLD.insert(Effects::DIMMED), 22),
+    (Effects::ITALIC, 23),
+    (
+        Effects::UNDERLINE
+            .insert(Effects::DOUBLE_UNDERLINE)
+            .insert(Effects::CURLY_UNDERLINE)
+            .insert(Effects::DOTTED_UNDERLINE)
+            .insert(Effects::DASHED_UNDERLINE),
+        24,
+    ),
+    (Effects::BLINK, 25),
+    (Effects::INVERT, 27),
+    (Effects::HIDDEN, 28),
+    (Effects::STRIKETHROUGH, 29),
+];
+
+fn intersect(a: Effects, b: Effects) -> Effects {
+    a - (a - b)
+}
+
+/// Write a SGR sequence which changes terminal style from `from` to `to`.
+fn transition(f: &mut Formatter<'_>, from: &Style, to: &Style) -> Result {
+    if from == to {
+        return Ok(());
+    }
+
+    let (from_effects, to_effects) = (from.get_effects(), to.get_effects());
+    let mut add = to_effects - from_effects;
+    let mut params = SgrParams::default();
+
+    for (group, code) in OFF {
+        if !intersect(from_effects - to_effects, group).is_plain() {
+            params.push(code);
+            // The off code resets the whole group, so effects still needed must be added again.
+            add |= intersect(to_effects, group);
+        }
+    }
+
+    for (was, now, code) in [
+        (from.get_fg_color(), to.get_fg_color(), 39),
+        (from.get_bg_color(), to.get_bg_color(), 49),
+        (from.get_underline_color(), to.get_underline_color(), 59),
+    ] {
+        if was.is_some() && now.is_none() {
+            params.push(code);
+        }
+    }
+
+    let changed = |was, now| if was == now { None } else { now };
+    let add = Style::new()
+        .fg_color(changed(from.get_fg_color(), to.get_fg_color()))
+        .bg_color(changed(from.get_bg_color(), to.get_bg_color()))
+        .underline_color(changed(
+            from.get_underline_color(),
+            to.get_underline_color(),
+        ))
+        .effects(add);
+    params.push_rendered(&add.render().to_string());
+
+    params.write_to(f)
+}
+
+/// Display adapter of [`StyledText`], which writes only the difference between styles of adjacent
+/// spans.
+///
+/// Created by [`StyledText::minimal_sgr`].
+///
+/// Instead of a set-then-reset for every span, it writes only SGR codes needed to change from
+/// previous style to the next, and writes a single reset at the end of each line, and at the end of
+/// output, if needed. The visible result is the same as the normal [`Display`] of [`StyledText`],
+/// but with less bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct MinimalSgr<'t, 'a> {
+    text: &'t StyledText<'a>,
+}
+
+impl<'a> StyledText<'a> {
+    /// Display this text with minimal SGR transitions between spans, see [`MinimalSgr`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use tui_markup::{compile, generator::ANSIStringsGenerator};
+    ///
+    /// let text = compile::<ANSIStringsGenerator>("<b one <i two>> three").unwrap();
+    /// assert_eq!(
+    ///     text.minimal_sgr().to_string(),
+    ///     "\x1b[1mone \x1b[3mtwo\x1b[22;23m three"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn minimal_sgr(&self) -> MinimalSgr<'_, 'a> {
+        MinimalSgr { text: self }
+    }
+}
+
+impl Display for MinimalSgr<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut current = Style::new();
+
+        for span in self.text.spans() {
+            for (i, segment) in span.text().split('\n').enumerate() {
+                if i > 0 {
+                    if current != Style::new() {
+                        write!(f, "{}", Reset.render())?;
+                        current = Style::new();
+                    }
+                    f.write_char('\n')?;
+                }
+
+                if !segment.is_empty() {
+                    transition(f, &current, span.style())?;
+                    current = *span.style();
+                    f.write_str(segment)?;
+                }
+            }
+        }
+
+        if current != Style::new() {
+            write!(f, "{}", Reset.render())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{compile, generator::ANSIStringsGenerator};
+
+    macro_rules! test_minimal {
+        ($source:expr => $output:expr) => {
+            assert_eq!(
+                compile::<ANSIStringsGenerator>($source)
+                    .unwrap()
+                    .minimal_sgr()
+                    .to_string(),
+                $output
+            );
+        };
+    }
+
+    #[test]
+    fn test_plain_text() {
+        test_minimal!("" => "");
+        test_minimal!("one\ntwo" => "one\ntwo");
+    }
+
+    #[test]
+    fn test_only_difference() {
+        test_minimal!("<green a<b b>>" => "\x1b[32ma\x1b[1mb\x1b[0m");
+        test_minimal!("<red a><blue b>" => "\x1b[31ma\x1b[34mb\x1b[0m");
+        test_minimal!("<bg:red a>b" => "\x1b[41ma\x1b[49mb");
+        test_minimal!("<b x><b y>" => "\x1b[1mxy\x1b[0m");
+    }
+
+    #[test]
+    fn test_readd_effect_in_same_group() {
+        test_minimal!("<d a<b b>c>" => "\x1b[2ma\x1b[1mb\x1b[22;2mc\x1b[0m");
+        test_minimal!("<b,red a><i b>" => "\x1b[1;31ma\x1b[22;39;3mb\x1b[0m");
+    }
+
+    #[test]
+    fn test_reset_at_line_end() {
+        test_minimal!("<green one>\n<green two>\nthree" => "\x1b[32mone\x1b[0m\n\x1b[32mtwo\x1b[0m\nthree");
+    }
+}
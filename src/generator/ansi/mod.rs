@@ -2,16 +2,23 @@
 
 use anstyle::Style;
 
+mod minimal;
 mod span;
 mod tag;
+mod writer;
 
+pub use minimal::MinimalSgr;
 pub use span::{StyledSpan, StyledText};
 pub use tag::ANSITagConvertor;
 
 use super::{
     Generator,
-    helper::{CustomTagParser, GeneratorInfallible, NoopCustomTagParser, flatten},
+    helper::{
+        CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize, SourceMap,
+        SourceMapGenerator, flatten_into, flatten_merged_into,
+    },
 };
+use crate::parser::{ItemC, ItemG};
 
 /// Generator for ANSI terminal strings.
 ///
@@ -56,12 +63,16 @@ use super::{
 #[derive(Debug)]
 pub struct ANSIStringsGenerator<P = NoopCustomTagParser<Style>> {
     convertor: ANSITagConvertor<P>,
+    merge_spans: bool,
+    sanitize: Sanitize,
 }
 
 impl<P> Default for ANSIStringsGenerator<P> {
     fn default() -> Self {
         Self {
             convertor: ANSITagConvertor::<P>::default(),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
         }
     }
 }
@@ -71,8 +82,56 @@ impl<P> ANSIStringsGenerator<P> {
     pub fn new(p: P) -> Self {
         Self {
             convertor: ANSITagConvertor::new(p),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
         }
     }
+
+    /// Merge adjacent spans with same style into one, including text split by escapes. Disabled by
+    /// default.
+    ///
+    /// See [`flatten_merged`][super::helper::flatten_merged] for details.
+    #[must_use]
+    pub fn merge_spans(mut self, merge: bool) -> Self {
+        self.merge_spans = merge;
+        self
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.sanitize = policy;
+        self
+    }
+}
+
+impl<P> ANSIStringsGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn build<'a>(
+        &self, markup: Vec<Vec<ItemC<'a, ANSITagConvertor<P>>>>,
+        mut map: Option<&mut SourceMap<'a>>,
+    ) -> StyledText<'a> {
+        let mut spans = Vec::with_capacity(markup.len());
+
+        for (i, line) in markup.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(StyledSpan::new(Style::new(), "\n"));
+                if let Some(map) = map.as_deref_mut() {
+                    map.push_span([]);
+                }
+            }
+            if self.merge_spans {
+                flatten_merged_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            } else {
+                flatten_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            }
+        }
+
+        StyledText::new(spans)
+    }
 }
 
 impl<'a, P> Generator<'a> for ANSIStringsGenerator<P>
@@ -87,18 +146,18 @@ where
         &mut self.convertor
     }
 
-    fn generate(
-        &mut self, markup: Vec<Vec<crate::parser::ItemG<'a, Self>>>,
-    ) -> Result<Self::Output, Self::Err> {
-        let mut spans = Vec::with_capacity(markup.len());
-
-        for (i, line) in markup.into_iter().enumerate() {
-            if i > 0 {
-                spans.push(StyledSpan::new(Style::new(), "\n"));
-            }
-            spans.extend(flatten(line));
-        }
+    fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, None))
+    }
+}
 
-        Ok(StyledText::new(spans))
+impl<'a, P> SourceMapGenerator<'a> for ANSIStringsGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, Some(map)))
     }
 }
@@ -1,29 +1,33 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use anstyle::{Color, Style};
 
 use crate::generator::{
     Tag, TagConvertor,
-    helper::{FlattenableSpan, FlattenableStyle},
+    helper::{FlattenableCowSpan, FlattenableSpan, FlattenableStyle, IntoOwned},
 };
 
 /// A single styled text segment in the ANSI output.
 ///
 /// Stores a style and the text it applies to. The text borrows from the original markup input
-/// (zero-copy).
+/// (zero-copy), unless spans are merged by
+/// [`ANSIStringsGenerator::merge_spans`](crate::generator::ANSIStringsGenerator::merge_spans).
 ///
 /// Display writes `{style}{text}{style:#}` — the ANSI escape sequence, then the text, then the
 /// reset.
 #[derive(Debug, Clone)]
 pub struct StyledSpan<'a> {
     style: Style,
-    text: &'a str,
+    text: Cow<'a, str>,
 }
 
 impl<'a> StyledSpan<'a> {
     /// Create a new styled span.
-    pub fn new(style: Style, text: &'a str) -> Self {
-        Self { style, text }
+    pub fn new(style: Style, text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            style,
+            text: text.into(),
+        }
     }
 
     /// Get the style applied to this span.
@@ -32,8 +36,8 @@ impl<'a> StyledSpan<'a> {
     }
 
     /// Get the text content of this span.
-    pub fn text(&self) -> &'a str {
-        self.text
+    pub fn text(&self) -> &str {
+        &self.text
     }
 }
 
@@ -74,6 +78,19 @@ impl Display for StyledText<'_> {
     }
 }
 
+impl IntoOwned for StyledText<'_> {
+    type Owned = StyledText<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        StyledText::new(
+            self.spans
+                .into_iter()
+                .map(|span| StyledSpan::new(span.style, span.text.into_owned()))
+                .collect(),
+        )
+    }
+}
+
 impl<'a> From<Vec<StyledSpan<'a>>> for StyledText<'a> {
     fn from(spans: Vec<StyledSpan<'a>>) -> Self {
         Self::new(spans)
@@ -114,3 +131,9 @@ impl<'a> FlattenableSpan<'a, Style> for StyledSpan<'a> {
         Self::new(style.unwrap_or_default(), s)
     }
 }
+
+impl<'a> FlattenableCowSpan<'a, Style> for StyledSpan<'a> {
+    fn with_style_cow(s: Cow<'a, str>, style: Option<Style>) -> Self {
+        Self::new(style.unwrap_or_default(), s)
+    }
+}
@@ -0,0 +1,193 @@
+use std::{fmt, io};
+
+use anstyle::Style;
+
+use super::{ANSIStringsGenerator, StyledSpan};
+use crate::{
+    Error,
+    generator::{
+        TagConvertor,
+        helper::{CustomTagParser, IoWriter, WriteError, flatten_merged_with, flatten_with},
+    },
+    parser::parse_lines,
+};
+
+impl<P> ANSIStringsGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    /// Compile markup source and write the result into a [`fmt::Write`] directly.
+    ///
+    /// Source is parsed and written line by line, without building the whole [`StyledText`]
+    /// first, so memory usage does not grow with the size of source. The written content is the
+    /// same as displaying the result of [`compile_with`][crate::compile_with].
+    ///
+    /// If a line has invalid syntax, lines before it are already written.
+    ///
+    /// ## Errors
+    ///
+    /// If input source contains invalid syntax, or the writer fails. Both are located at the line
+    /// in source, see [`LocatedError`][crate::LocatedError].
+    ///
+    /// [`StyledText`]: super::StyledText
+    pub fn compile_to_fmt_writer<'a, W>(
+        &mut self, s: &'a str, writer: &mut W,
+    ) -> Result<(), Error<'a, WriteError<fmt::Error>>>
+    where
+        W: fmt::Write + ?Sized,
+    {
+        for (i, line) in parse_lines(s).enumerate() {
+            let line = self.convertor.convert_line(line?);
+            let located = |e| Error::Gen(WriteError::new(i, e));
+
+            if i > 0 {
+                writer.write_char('\n').map_err(located)?;
+            }
+
+            let spans: Vec<StyledSpan<'_>> = if self.merge_spans {
+                flatten_merged_with(line, self.sanitize)
+            } else {
+                flatten_with(line, self.sanitize)
+            };
+            for span in spans {
+                write!(writer, "{span}").map_err(located)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compile markup source and write the result into a [`io::Write`] directly.
+    ///
+    /// See [`compile_to_fmt_writer`][Self::compile_to_fmt_writer] for details. The writer is not
+    /// buffered, wrap it in a [`io::BufWriter`] if needed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use tui_markup::generator::ANSIStringsGenerator;
+    ///
+    /// let mut output = Vec::new();
+    /// <ANSIStringsGenerator>::default()
+    ///     .compile_to_writer("<green ok>\n<b done>", &mut output)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "\x1b[32mok\x1b[0m\n\x1b[1mdone\x1b[0m"
+    /// );
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`compile_to_fmt_writer`][Self::compile_to_fmt_writer], with the [`io::Error`] of
+    /// writer.
+    pub fn compile_to_writer<'a, W>(
+        &mut self, s: &'a str, writer: W,
+    ) -> Result<(), Error<'a, WriteError<io::Error>>>
+    where
+        W: io::Write,
+    {
+        let mut writer = IoWriter::new(writer);
+        self.compile_to_fmt_writer(s, &mut writer)
+            .map_err(|e| match e {
+                Error::Parse(e) => Error::Parse(e),
+                Error::Gen(e) => Error::Gen(e.with_error(writer.take_error())),
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Error, LocatedError, compile, generator::ANSIStringsGenerator};
+
+    #[test]
+    fn test_same_as_compile() {
+        let source = "<bg:blue one <green,b two>> \\<three\\>\n\n<208 four>";
+
+        let mut output = String::new();
+        <ANSIStringsGenerator>::default()
+            .compile_to_fmt_writer(source, &mut output)
+            .unwrap();
+
+        assert_eq!(
+            output,
+            compile::<ANSIStringsGenerator>(source).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_sanitize() {
+        use crate::generator::helper::Sanitize;
+
+        let source = "name\x1b]52;c;eA==\x07 <b \x1b[2J>";
+        let output = |mut g: ANSIStringsGenerator| {
+            let mut output = String::new();
+            g.compile_to_fmt_writer(source, &mut output).unwrap();
+            output
+        };
+
+        assert_eq!(
+            output(ANSIStringsGenerator::default()),
+            "name^[]52;c;eA==^G \x1b[1m^[\x1b[0m\x1b[1m[2J\x1b[0m"
+        );
+        assert_eq!(
+            output(ANSIStringsGenerator::default().sanitize(Sanitize::Strip)),
+            "name]52;c;eA== \x1b[1m[2J\x1b[0m"
+        );
+        assert_eq!(
+            output(ANSIStringsGenerator::default().sanitize(Sanitize::Keep)),
+            "name\x1b]52;c;eA==\x07 \x1b[1m\x1b[2J\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_write_until_error_line() {
+        let mut output = Vec::new();
+        let result =
+            <ANSIStringsGenerator>::default().compile_to_writer("one\n<b two", &mut output);
+
+        assert!(matches!(result, Err(Error::Parse(_))));
+        assert_eq!(output, b"one");
+    }
+
+    #[test]
+    fn test_error_location() {
+        let source = "one\ntwo <b three";
+        let expected = compile::<ANSIStringsGenerator>(source).unwrap_err();
+
+        let mut output = String::new();
+        let error = <ANSIStringsGenerator>::default()
+            .compile_to_fmt_writer(source, &mut output)
+            .unwrap_err();
+        assert_eq!(error.location(), (2, 5));
+        assert_eq!(error.location(), expected.location());
+
+        let error = <ANSIStringsGenerator>::default()
+            .compile_to_writer(source, Vec::new())
+            .unwrap_err();
+        assert_eq!(error.location(), (2, 5));
+    }
+
+    #[test]
+    fn test_io_error() {
+        struct Broken;
+
+        impl std::io::Write for Broken {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let result = <ANSIStringsGenerator>::default().compile_to_writer("x", Broken);
+        let Err(Error::Gen(e)) = result else {
+            panic!("expect io error");
+        };
+        assert_eq!(e.location(), (1, 1));
+        assert_eq!(e.into_inner().kind(), std::io::ErrorKind::BrokenPipe);
+    }
+}
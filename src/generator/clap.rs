@@ -0,0 +1,139 @@
+//! Generator for clap crate's styled help text.
+
+use std::fmt::Write;
+
+use anstyle::Style;
+use clap_builder::builder::StyledStr;
+
+use crate::{
+    generator::{
+        Generator,
+        ansi::{ANSIStringsGenerator, ANSITagConvertor, StyledText},
+        helper::{CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize},
+    },
+    parser::ItemG,
+};
+
+/// Generator for `clap` crate's [`StyledStr`] type.
+///
+/// It supports the same tags as [`ANSIStringsGenerator`], see [docs/ansi-tags.ebnf].
+///
+/// The styles are stored in the [`StyledStr`], so clap decides whether to show them based on its
+/// own color settings (`--color never`, not a TTY, etc.).
+///
+/// Notice that clap only renders these styles when its `color` feature is enabled (the default);
+/// otherwise they are always stripped.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::{compile, generator::ClapStyledStrGenerator};
+///
+/// let about = compile::<ClapStyledStrGenerator>("A <b,green tiny> CLI").unwrap();
+///
+/// assert_eq!(about.to_string(), "A tiny CLI");
+///
+/// let cmd = clap_builder::Command::new("tiny").about(about);
+/// ```
+///
+/// ### With custom tags
+///
+/// ```
+/// use anstyle::{AnsiColor, Style};
+/// use tui_markup::{compile_with, generator::ClapStyledStrGenerator};
+///
+/// let g = ClapStyledStrGenerator::new(|tag: &str| match tag {
+///     "flag" => Some(Style::new().bold().fg_color(Some(AnsiColor::Cyan.into()))),
+///     _ => None,
+/// });
+///
+/// let after_help = compile_with("Use <flag --verbose> to see more", g).unwrap();
+///
+/// assert_eq!(after_help.to_string(), "Use --verbose to see more");
+/// ```
+///
+/// [docs/ansi-tags.ebnf]: https://github.com/7sDream/tui-markup/blob/master/docs/ansi-tags.ebnf
+#[derive(Debug)]
+pub struct ClapStyledStrGenerator<P = NoopCustomTagParser<Style>> {
+    inner: ANSIStringsGenerator<P>,
+}
+
+impl<P> Default for ClapStyledStrGenerator<P> {
+    fn default() -> Self {
+        Self {
+            inner: ANSIStringsGenerator::<P>::default(),
+        }
+    }
+}
+
+impl<P> ClapStyledStrGenerator<P> {
+    /// Create a new generator, with a custom tag parser.
+    pub fn new(p: P) -> Self {
+        Self {
+            inner: ANSIStringsGenerator::new(p),
+        }
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.inner = self.inner.sanitize(policy);
+        self
+    }
+}
+
+impl<'a, P> Generator<'a> for ClapStyledStrGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    type Convertor = ANSITagConvertor<P>;
+    type Err = GeneratorInfallible;
+    type Output = StyledStr;
+
+    fn convertor(&mut self) -> &mut Self::Convertor {
+        self.inner.convertor()
+    }
+
+    fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
+        self.inner.generate(markup).map(StyledStr::from)
+    }
+}
+
+impl From<StyledText<'_>> for StyledStr {
+    fn from(text: StyledText<'_>) -> Self {
+        let mut result = StyledStr::new();
+        write!(result, "{text}").expect("write to StyledStr never fails");
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt::Write;
+
+    use anstyle::{AnsiColor, Style};
+    use clap_builder::builder::StyledStr;
+
+    use crate::{compile, generator::ClapStyledStrGenerator};
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(
+            compile::<ClapStyledStrGenerator>("plain\ntext"),
+            Ok(StyledStr::from("plain\ntext"))
+        );
+    }
+
+    #[test]
+    fn test_styled_text() {
+        let style = Style::new().bold().fg_color(Some(AnsiColor::Green.into()));
+        let mut expected = StyledStr::new();
+        write!(expected, "A {style}tiny{style:#} CLI").unwrap();
+
+        let result = compile::<ClapStyledStrGenerator>("A <b,green tiny> CLI").unwrap();
+
+        assert_eq!(result, expected);
+        assert_eq!(result.to_string(), "A tiny CLI");
+    }
+}
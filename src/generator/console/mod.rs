@@ -0,0 +1,226 @@
+//! Generator implementations for console crate.
+
+mod span;
+
+use std::borrow::Cow;
+
+use anstyle::Style;
+use console::StyledObject;
+pub use span::console_style;
+
+use crate::{
+    generator::{
+        Generator,
+        ansi::ANSITagConvertor,
+        helper::{
+            CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize, SourceMap,
+            SourceMapGenerator, flatten_into, flatten_merged_into,
+        },
+    },
+    parser::{ItemC, ItemG},
+};
+
+/// Generator for [console crate][console], generated result is a series of it's
+/// [`StyledObject`]s, which are also used by `indicatif` and `dialoguer`.
+///
+/// It supports the same tags as [`ANSIStringsGenerator`][super::ANSIStringsGenerator], see
+/// [docs/ansi-tags.ebnf].
+///
+/// Because [`console::Style`] can't be inspected and merged, custom tags are provided as
+/// [`anstyle::Style`], and converted by [`console_style`] after all styles are flattened.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::{compile, generator::ConsoleStyledObjectsGenerator};
+///
+/// let objects = compile::<ConsoleStyledObjectsGenerator>("I have a <green green text>").unwrap();
+///
+/// let text: String = objects.iter().map(|o| o.to_string()).collect();
+/// println!("{}", text);
+/// ```
+///
+/// ### With custom tags
+///
+/// ```
+/// use anstyle::{AnsiColor, Style};
+/// use tui_markup::{compile_with, generator::ConsoleStyledObjectsGenerator};
+///
+/// let g = ConsoleStyledObjectsGenerator::new(|tag: &str| match tag {
+///     "keyboard" => Some(Style::new().bold().fg_color(Some(AnsiColor::Blue.into()))),
+///     _ => None,
+/// });
+///
+/// let objects = compile_with("Press <keyboard W> to move up", g).unwrap();
+///
+/// for o in &objects {
+///     print!("{}", o);
+/// }
+/// ```
+///
+/// ### Show output
+///
+/// Print each [`StyledObject`], console decides whether to show styles by it's own color settings,
+/// see `console::colors_enabled`.
+///
+/// [docs/ansi-tags.ebnf]: https://github.com/7sDream/tui-markup/blob/master/docs/ansi-tags.ebnf
+#[derive(Debug)]
+pub struct ConsoleStyledObjectsGenerator<P = NoopCustomTagParser<Style>> {
+    convertor: ANSITagConvertor<P>,
+    merge_spans: bool,
+    sanitize: Sanitize,
+}
+
+impl<P> Default for ConsoleStyledObjectsGenerator<P> {
+    fn default() -> Self {
+        Self {
+            convertor: ANSITagConvertor::<P>::default(),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
+        }
+    }
+}
+
+impl<P> ConsoleStyledObjectsGenerator<P> {
+    /// Create a new generator, with a custom tag parser.
+    pub fn new(p: P) -> Self {
+        Self {
+            convertor: ANSITagConvertor::new(p),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
+        }
+    }
+
+    /// Merge adjacent spans with same style into one, including text split by escapes. Disabled by
+    /// default.
+    ///
+    /// See [`flatten_merged`][super::helper::flatten_merged] for details.
+    #[must_use]
+    pub fn merge_spans(mut self, merge: bool) -> Self {
+        self.merge_spans = merge;
+        self
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.sanitize = policy;
+        self
+    }
+}
+
+impl<P> ConsoleStyledObjectsGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn build<'a>(
+        &self, markup: Vec<Vec<ItemC<'a, ANSITagConvertor<P>>>>,
+        mut map: Option<&mut SourceMap<'a>>,
+    ) -> Vec<StyledObject<Cow<'a, str>>> {
+        let mut spans = Vec::with_capacity(markup.len());
+        for (i, line) in markup.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(console::Style::new().apply_to(Cow::Borrowed("\n")));
+                if let Some(map) = map.as_deref_mut() {
+                    map.push_span([]);
+                }
+            }
+            if self.merge_spans {
+                flatten_merged_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            } else {
+                flatten_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            }
+        }
+        spans
+    }
+}
+
+impl<'a, P> Generator<'a> for ConsoleStyledObjectsGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    type Convertor = ANSITagConvertor<P>;
+    type Err = GeneratorInfallible;
+    type Output = Vec<StyledObject<Cow<'a, str>>>;
+
+    fn convertor(&mut self) -> &mut Self::Convertor {
+        &mut self.convertor
+    }
+
+    fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, None))
+    }
+}
+
+impl<'a, P> SourceMapGenerator<'a> for ConsoleStyledObjectsGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, Some(map)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compile, compile_with, compile_with_source_map, generator::ConsoleStyledObjectsGenerator,
+    };
+
+    fn render(s: &str) -> String {
+        compile::<ConsoleStyledObjectsGenerator>(s)
+            .unwrap()
+            .into_iter()
+            .map(|o| o.force_styling(true).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_styled_objects() {
+        assert_eq!(
+            render("<bg:blue one <green,b two>>\nthree"),
+            "\u{1b}[44mone \u{1b}[0m\u{1b}[32m\u{1b}[44m\u{1b}[1mtwo\u{1b}[0m\nthree"
+        );
+    }
+
+    #[test]
+    fn test_indexed_and_rgb_color() {
+        assert_eq!(render("<208 x>"), "\u{1b}[38;5;208mx\u{1b}[0m");
+        assert_eq!(render("<bg:ff8000 x>"), "\u{1b}[48;2;255;128;0mx\u{1b}[0m");
+    }
+
+    #[test]
+    fn test_merge_spans() {
+        let generator = <ConsoleStyledObjectsGenerator>::default().merge_spans(true);
+        let objects = compile_with("a\\<b<b c><b d>", generator).unwrap();
+
+        let texts: Vec<_> = objects
+            .iter()
+            .map(|o| o.clone().force_styling(false).to_string())
+            .collect();
+        assert_eq!(texts, vec!["a<b", "cd"]);
+
+        let rendered: String = objects
+            .into_iter()
+            .map(|o| o.force_styling(true).to_string())
+            .collect();
+        assert_eq!(rendered, "a<b\u{1b}[1mcd\u{1b}[0m");
+    }
+
+    #[test]
+    fn test_source_map() {
+        let source = "<b a>\n\x1b";
+        let (objects, map) =
+            compile_with_source_map(source, <ConsoleStyledObjectsGenerator>::default()).unwrap();
+
+        assert_eq!(objects.len(), 3);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+            Some(3..4),
+            None,
+            Some(6..7)
+        ]);
+    }
+}
@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use anstyle::{AnsiColor, Color as AnsiStyleColor, Effects, Style};
+use console::{Attribute, Color, StyledObject};
+
+use crate::generator::helper::{FlattenableCowSpan, FlattenableSpan};
+
+fn color(c: AnsiStyleColor) -> (Color, bool) {
+    match c {
+        AnsiStyleColor::Ansi(c) => {
+            let base = match c {
+                AnsiColor::Black | AnsiColor::BrightBlack => Color::Black,
+                AnsiColor::Red | AnsiColor::BrightRed => Color::Red,
+                AnsiColor::Green | AnsiColor::BrightGreen => Color::Green,
+                AnsiColor::Yellow | AnsiColor::BrightYellow => Color::Yellow,
+                AnsiColor::Blue | AnsiColor::BrightBlue => Color::Blue,
+                AnsiColor::Magenta | AnsiColor::BrightMagenta => Color::Magenta,
+                AnsiColor::Cyan | AnsiColor::BrightCyan => Color::Cyan,
+                AnsiColor::White | AnsiColor::BrightWhite => Color::White,
+            };
+            (base, c.is_bright())
+        }
+        AnsiStyleColor::Ansi256(c) => (Color::Color256(c.0), false),
+        AnsiStyleColor::Rgb(c) => (Color::TrueColor(c.0, c.1, c.2), false),
+    }
+}
+
+const ATTRIBUTES: [(Effects, Attribute); 8] = [
+    (Effects::BOLD, Attribute::Bold),
+    (Effects::DIMMED, Attribute::Dim),
+    (Effects::ITALIC, Attribute::Italic),
+    (Effects::UNDERLINE, Attribute::Underlined),
+    (Effects::BLINK, Attribute::Blink),
+    (Effects::INVERT, Attribute::Reverse),
+    (Effects::HIDDEN, Attribute::Hidden),
+    (Effects::STRIKETHROUGH, Attribute::StrikeThrough),
+];
+
+/// Convert an [anstyle][anstyle::Style] style into a [console][console::Style] style.
+pub fn console_style(style: Style) -> console::Style {
+    let mut result = console::Style::new();
+
+    if let Some((c, bright)) = style.get_fg_color().map(color) {
+        result = result.fg(c);
+        if bright {
+            result = result.bright();
+        }
+    }
+
+    if let Some((c, bright)) = style.get_bg_color().map(color) {
+        result = result.bg(c);
+        if bright {
+            result = result.on_bright();
+        }
+    }
+
+    let effects = style.get_effects();
+    for (effect, attr) in ATTRIBUTES {
+        if effects.contains(effect) {
+            result = result.attr(attr);
+        }
+    }
+
+    result
+}
+
+impl<'a> FlattenableSpan<'a, Style> for StyledObject<Cow<'a, str>> {
+    fn with_style(s: &'a str, style: Option<Style>) -> Self {
+        Self::with_style_cow(Cow::Borrowed(s), style)
+    }
+}
+
+impl<'a> FlattenableCowSpan<'a, Style> for StyledObject<Cow<'a, str>> {
+    fn with_style_cow(s: Cow<'a, str>, style: Option<Style>) -> Self {
+        console_style(style.unwrap_or_default()).apply_to(s)
+    }
+}
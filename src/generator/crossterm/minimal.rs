@@ -0,0 +1,223 @@
+use std::fmt::Write;
+
+use crossterm::{
+    Command,
+    style::{
+        Attribute, Attributes, Color, ContentStyle, Print, SetAttribute, SetAttributes,
+        SetBackgroundColor, SetForegroundColor, SetUnderlineColor,
+    },
+};
+
+use super::Span;
+use crate::generator::helper::SgrParams;
+
+/// Attributes reset by the same SGR code.
+const OFF: [(&[Attribute], Attribute); 9] = [
+    (
+        &[Attribute::Bold, Attribute::Dim],
+        Attribute::NormalIntensity,
+    ),
+    (
+        &[Attribute::Italic, Attribute::Fraktur],
+        Attribute::NoItalic,
+    ),
+    (
+        &[
+            Attribute::Underlined,
+            Attribute::DoubleUnderlined,
+            Attribute::Undercurled,
+            Attribute::Underdotted,
+            Attribute::Underdashed,
+        ],
+        Attribute::NoUnderline,
+    ),
+    (
+        &[Attribute::SlowBlink, Attribute::RapidBlink],
+        Attribute::NoBlink,
+    ),
+    (&[Attribute::Reverse], Attribute::NoReverse),
+    (&[Attribute::Hidden], Attribute::NoHidden),
+    (&[Attribute::CrossedOut], Attribute::NotCrossedOut),
+    (
+        &[Attribute::Framed, Attribute::Encircled],
+        Attribute::NotFramedOrEncircled,
+    ),
+    (&[Attribute::OverLined], Attribute::NotOverLined),
+];
+
+fn span_parts<'s>(span: &'s Span<'_>) -> (ContentStyle, &'s str) {
+    match span {
+        Span::NoStyle(p) => (ContentStyle::default(), &p.0),
+        Span::Styled(p) => (*p.0.style(), p.0.content()),
+    }
+}
+
+/// Write commands which change terminal style from `from` to `to`.
+fn transition(f: &mut impl Write, from: &ContentStyle, to: &ContentStyle) -> std::fmt::Result {
+    if from == to {
+        return Ok(());
+    }
+
+    let mut add = Attributes::none();
+    for attr in Attribute::iterator() {
+        if to.attributes.has(attr) && !from.attributes.has(attr) {
+            add.set(attr);
+        }
+    }
+
+    // Commands are rendered into a buffer, and their parameters are written as a single sequence
+    let mut rendered = String::new();
+    for (group, off) in OFF {
+        if group
+            .iter()
+            .any(|a| from.attributes.has(*a) && !to.attributes.has(*a))
+        {
+            SetAttribute(off).write_ansi(&mut rendered)?;
+            // The off code resets the whole group, so attributes still needed must be set again.
+            group
+                .iter()
+                .filter(|a| to.attributes.has(**a))
+                .for_each(|a| add.set(*a));
+        }
+    }
+
+    let changed = |was: Option<Color>, now: Option<Color>| match (was, now) {
+        (Some(_), None) => Some(Color::Reset),
+        (was, Some(now)) if was != Some(now) => Some(now),
+        _ => None,
+    };
+
+    if let Some(c) = changed(from.foreground_color, to.foreground_color) {
+        SetForegroundColor(c).write_ansi(&mut rendered)?;
+    }
+    if let Some(c) = changed(from.background_color, to.background_color) {
+        SetBackgroundColor(c).write_ansi(&mut rendered)?;
+    }
+    if let Some(c) = changed(from.underline_color, to.underline_color) {
+        SetUnderlineColor(c).write_ansi(&mut rendered)?;
+    }
+
+    if !add.is_empty() {
+        SetAttributes(add).write_ansi(&mut rendered)?;
+    }
+
+    let mut params = SgrParams::default();
+    params.push_rendered(&rendered);
+    params.write_to(f)
+}
+
+/// A crossterm [Command] that prints output of
+/// [`CrosstermCommandsGenerator`][crate::generator::CrosstermCommandsGenerator] with minimal style
+/// transitions.
+///
+/// Executing each [`Span`] sets the full style before text and resets it after. This command writes
+/// only the difference between styles of adjacent spans instead, and a single reset at the end of
+/// each line, and at the end of output, if needed. The visible result is the same, but with less
+/// bytes.
+///
+/// On legacy Windows console without ANSI support, spans are just executed one by one.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Write;
+///
+/// use crossterm::QueueableCommand;
+/// use tui_markup::{
+///     compile,
+///     generator::{CrosstermCommandsGenerator, crossterm::MinimalSgr},
+/// };
+///
+/// let spans = compile::<CrosstermCommandsGenerator>("<b one <i two>> three").unwrap();
+///
+/// let mut stdout = std::io::stdout();
+/// stdout.queue(MinimalSgr::new(&spans)).unwrap();
+/// stdout.flush().unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MinimalSgr<'s, 'a> {
+    spans: &'s [Span<'a>],
+}
+
+impl<'s, 'a> MinimalSgr<'s, 'a> {
+    /// Create the command from output of the generator.
+    #[must_use]
+    pub fn new(spans: &'s [Span<'a>]) -> Self {
+        Self { spans }
+    }
+}
+
+impl Command for MinimalSgr<'_, '_> {
+    fn write_ansi(&self, f: &mut impl Write) -> std::fmt::Result {
+        let mut current = ContentStyle::default();
+
+        for span in self.spans {
+            let (style, text) = span_parts(span);
+            for (i, segment) in text.split('\n').enumerate() {
+                if i > 0 {
+                    if current != ContentStyle::default() {
+                        SetAttribute(Attribute::Reset).write_ansi(f)?;
+                        current = ContentStyle::default();
+                    }
+                    f.write_char('\n')?;
+                }
+
+                if !segment.is_empty() {
+                    transition(f, &current, &style)?;
+                    current = style;
+                    Print(segment).write_ansi(f)?;
+                }
+            }
+        }
+
+        if current != ContentStyle::default() {
+            SetAttribute(Attribute::Reset).write_ansi(f)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        self.spans.iter().try_for_each(Command::execute_winapi)
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        self.spans.iter().all(Command::is_ansi_code_supported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::Command;
+
+    use super::MinimalSgr;
+    use crate::{compile, generator::CrosstermCommandsGenerator};
+
+    fn render(s: &str) -> String {
+        let spans = compile::<CrosstermCommandsGenerator>(s).unwrap();
+        let mut result = String::new();
+        MinimalSgr::new(&spans).write_ansi(&mut result).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_only_difference() {
+        assert_eq!(render("<green a<b b>>"), "\x1b[38;5;2ma\x1b[1mb\x1b[0m");
+        assert_eq!(render("<bg:red a>b"), "\x1b[48;5;1ma\x1b[49mb");
+        assert_eq!(render("<d a<b b>c>"), "\x1b[2ma\x1b[1mb\x1b[22;2mc\x1b[0m");
+        assert_eq!(
+            render("<b,red a><i b>"),
+            "\x1b[38;5;1;1ma\x1b[22;39;3mb\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_reset_at_line_end() {
+        assert_eq!(
+            render("<b one>\n<b two>\nthree"),
+            "\x1b[1mone\x1b[0m\n\x1b[1mtwo\x1b[0m\nthree"
+        );
+    }
+}
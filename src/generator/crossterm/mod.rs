@@ -1,18 +1,24 @@
 //! Generator implementations for crossterm crate.
 
+mod minimal;
 mod span;
 mod tag;
+mod writer;
 
 use crossterm::style::{ContentStyle, Print};
+pub use minimal::MinimalSgr;
 pub use span::Span;
 pub use tag::CrosstermTagConvertor;
 
 use crate::{
     generator::{
         Generator,
-        helper::{CustomTagParser, GeneratorInfallible, NoopCustomTagParser, flatten},
+        helper::{
+            CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize, SourceMap,
+            SourceMapGenerator, flatten_into, flatten_merged_into,
+        },
     },
-    parser::ItemG,
+    parser::{ItemC, ItemG},
 };
 
 /// Generator for [crossterm crate][crossterm], generated result is a series of it's
@@ -46,12 +52,16 @@ use crate::{
 #[derive(Debug)]
 pub struct CrosstermCommandsGenerator<P = NoopCustomTagParser<ContentStyle>> {
     convertor: CrosstermTagConvertor<P>,
+    merge_spans: bool,
+    sanitize: Sanitize,
 }
 
 impl<P> Default for CrosstermCommandsGenerator<P> {
     fn default() -> Self {
         Self {
             convertor: CrosstermTagConvertor::<P>::default(),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
         }
     }
 }
@@ -61,8 +71,54 @@ impl<P> CrosstermCommandsGenerator<P> {
     pub fn new(p: P) -> Self {
         Self {
             convertor: CrosstermTagConvertor::new(p),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
         }
     }
+
+    /// Merge adjacent spans with same style into one, including text split by escapes. Disabled by
+    /// default.
+    ///
+    /// See [`flatten_merged`][super::helper::flatten_merged] for details.
+    #[must_use]
+    pub fn merge_spans(mut self, merge: bool) -> Self {
+        self.merge_spans = merge;
+        self
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.sanitize = policy;
+        self
+    }
+}
+
+impl<P> CrosstermCommandsGenerator<P>
+where
+    P: CustomTagParser<Output = ContentStyle>,
+{
+    fn build<'a>(
+        &self, markup: Vec<Vec<ItemC<'a, CrosstermTagConvertor<P>>>>,
+        mut map: Option<&mut SourceMap<'a>>,
+    ) -> Vec<Span<'a>> {
+        let mut spans = Vec::with_capacity(markup.len());
+        for (i, line) in markup.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::NoStyle(Print("\n".into())));
+                if let Some(map) = map.as_deref_mut() {
+                    map.push_span([]);
+                }
+            }
+            if self.merge_spans {
+                flatten_merged_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            } else {
+                flatten_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            }
+        }
+        spans
+    }
 }
 
 impl<'a, P> Generator<'a> for CrosstermCommandsGenerator<P>
@@ -78,13 +134,79 @@ where
     }
 
     fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
-        let mut spans = Vec::with_capacity(markup.len());
-        for (i, line) in markup.into_iter().enumerate() {
-            if i > 0 {
-                spans.push(Span::NoStyle(Print("\n")));
-            }
-            spans.extend(flatten(line));
+        Ok(self.build(markup, None))
+    }
+}
+
+impl<'a, P> SourceMapGenerator<'a> for CrosstermCommandsGenerator<P>
+where
+    P: CustomTagParser<Output = ContentStyle>,
+{
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, Some(map)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::{Command, style::Attribute};
+
+    use super::{CrosstermCommandsGenerator, Span};
+    use crate::{compile, compile_with_source_map, generator::helper::IntoOwned};
+
+    fn text<'s>(span: &'s Span<'_>) -> &'s str {
+        match span {
+            Span::NoStyle(p) => &p.0,
+            Span::Styled(p) => p.0.content(),
+        }
+    }
+
+    #[test]
+    fn test_merge_spans() {
+        let source = "a\\<b<b c><b d>\n<b e>";
+        let generator = <CrosstermCommandsGenerator>::default().merge_spans(true);
+        let (spans, map) = compile_with_source_map(source, generator).unwrap();
+
+        assert_eq!(spans.iter().map(text).collect::<Vec<_>>(), vec![
+            "a<b", "cd", "\n", "e"
+        ]);
+        assert!(
+            matches!(&spans[1], Span::Styled(p) if p.0.style().attributes.has(Attribute::Bold))
+        );
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+            Some(0..4),
+            Some(7..13),
+            None,
+            Some(18..19)
+        ]);
+        assert_eq!(map.byte_offset(1, 1), Some(12));
+
+        let mut merged = String::new();
+        for span in &spans {
+            span.write_ansi(&mut merged).unwrap();
+        }
+        let mut output = String::new();
+        <CrosstermCommandsGenerator>::default()
+            .merge_spans(true)
+            .compile_to_fmt_writer(source, &mut output)
+            .unwrap();
+        assert_eq!(output, merged);
+    }
+
+    #[test]
+    fn test_into_owned() {
+        let spans = compile::<CrosstermCommandsGenerator>("a<b,green b>\n<i c>").unwrap();
+        let mut borrowed = String::new();
+        for span in &spans {
+            span.write_ansi(&mut borrowed).unwrap();
+        }
+
+        let mut owned = String::new();
+        for span in &spans.into_owned() {
+            span.write_ansi(&mut owned).unwrap();
         }
-        Ok(spans)
+        assert_eq!(owned, borrowed);
     }
 }
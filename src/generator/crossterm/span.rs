@@ -1,11 +1,13 @@
+use std::borrow::Cow;
+
 use crossterm::{
     Command,
-    style::{Attributes, Color, ContentStyle, Print, PrintStyledContent, Stylize},
+    style::{Attributes, Color, ContentStyle, Print, PrintStyledContent, StyledContent, Stylize},
 };
 
 use crate::generator::{
     Tag, TagConvertor,
-    helper::{FlattenableSpan, FlattenableStyle},
+    helper::{FlattenableCowSpan, FlattenableSpan, FlattenableStyle, IntoOwned},
 };
 
 impl<'a, C> From<Tag<'a, C>> for ContentStyle
@@ -43,16 +45,25 @@ impl FlattenableStyle for ContentStyle {
 }
 
 /// Span is a crossterm Command for print raw or styled text.
+///
+/// The text borrows from the markup source, unless spans are merged by
+/// [`CrosstermCommandsGenerator::merge_spans`](super::CrosstermCommandsGenerator::merge_spans).
 #[derive(Debug)]
 pub enum Span<'a> {
     /// Print raw text
-    NoStyle(Print<&'a str>),
+    NoStyle(Print<Cow<'a, str>>),
     /// Print styled text
-    Styled(PrintStyledContent<&'a str>),
+    Styled(PrintStyledContent<Cow<'a, str>>),
 }
 
 impl<'a> FlattenableSpan<'a, ContentStyle> for Span<'a> {
     fn with_style(s: &'a str, style: Option<ContentStyle>) -> Self {
+        Self::with_style_cow(Cow::Borrowed(s), style)
+    }
+}
+
+impl<'a> FlattenableCowSpan<'a, ContentStyle> for Span<'a> {
+    fn with_style_cow(s: Cow<'a, str>, style: Option<ContentStyle>) -> Self {
         match style {
             Some(style) => Span::Styled(PrintStyledContent(style.apply(s))),
             None => Span::NoStyle(Print(s)),
@@ -60,6 +71,22 @@ impl<'a> FlattenableSpan<'a, ContentStyle> for Span<'a> {
     }
 }
 
+impl IntoOwned for Vec<Span<'_>> {
+    type Owned = Vec<Span<'static>>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter()
+            .map(|span| match span {
+                Span::NoStyle(p) => Span::NoStyle(Print(Cow::Owned(p.0.into_owned()))),
+                Span::Styled(p) => Span::Styled(PrintStyledContent(StyledContent::new(
+                    *p.0.style(),
+                    Cow::Owned(p.0.content().to_string()),
+                ))),
+            })
+            .collect()
+    }
+}
+
 impl<'a> Command for Span<'a> {
     fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
         match self {
@@ -0,0 +1,165 @@
+use std::{fmt, io};
+
+use crossterm::{
+    Command, QueueableCommand,
+    style::{ContentStyle, Print},
+};
+
+use super::{CrosstermCommandsGenerator, Span};
+use crate::{
+    Error,
+    generator::{
+        TagConvertor,
+        helper::{CustomTagParser, WriteError, flatten_merged_with, flatten_with},
+    },
+    parser::parse_lines,
+};
+
+impl<P> CrosstermCommandsGenerator<P>
+where
+    P: CustomTagParser<Output = ContentStyle>,
+{
+    /// Compile markup source and write ANSI sequences of the result into a [`fmt::Write`] directly.
+    ///
+    /// Source is parsed and written line by line, without building the whole command list first,
+    /// so memory usage does not grow with the size of source.
+    ///
+    /// If a line has invalid syntax, lines before it are already written.
+    ///
+    /// ## Errors
+    ///
+    /// If input source contains invalid syntax, or the writer fails. Both are located at the line
+    /// in source, see [`LocatedError`][crate::LocatedError].
+    pub fn compile_to_fmt_writer<'a, W>(
+        &mut self, s: &'a str, writer: &mut W,
+    ) -> Result<(), Error<'a, WriteError<fmt::Error>>>
+    where
+        W: fmt::Write,
+    {
+        for (i, line) in parse_lines(s).enumerate() {
+            let line = self.convertor.convert_line(line?);
+            let located = |e| Error::Gen(WriteError::new(i, e));
+
+            if i > 0 {
+                Print("\n").write_ansi(writer).map_err(located)?;
+            }
+
+            let spans: Vec<Span<'_>> = if self.merge_spans {
+                flatten_merged_with(line, self.sanitize)
+            } else {
+                flatten_with(line, self.sanitize)
+            };
+            for span in spans {
+                span.write_ansi(writer).map_err(located)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compile markup source and queue commands of the result into a [`io::Write`] directly.
+    ///
+    /// Same as [`compile_to_fmt_writer`][Self::compile_to_fmt_writer], but commands are queued by
+    /// [`QueueableCommand`], so legacy Windows console is also supported. The writer is not
+    /// flushed.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use tui_markup::generator::CrosstermCommandsGenerator;
+    ///
+    /// let mut stdout = std::io::stdout();
+    /// <CrosstermCommandsGenerator>::default()
+    ///     .compile_to_writer("I have a <green green text>", &mut stdout)
+    ///     .unwrap();
+    /// stdout.flush().unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`compile_to_fmt_writer`][Self::compile_to_fmt_writer], with the [`io::Error`] of
+    /// writer.
+    pub fn compile_to_writer<'a, W>(
+        &mut self, s: &'a str, mut writer: W,
+    ) -> Result<(), Error<'a, WriteError<io::Error>>>
+    where
+        W: io::Write,
+    {
+        for (i, line) in parse_lines(s).enumerate() {
+            let line = self.convertor.convert_line(line?);
+            let located = |e| Error::Gen(WriteError::new(i, e));
+
+            if i > 0 {
+                writer.queue(Print("\n")).map_err(located)?;
+            }
+
+            let spans: Vec<Span<'_>> = if self.merge_spans {
+                flatten_merged_with(line, self.sanitize)
+            } else {
+                flatten_with(line, self.sanitize)
+            };
+            for span in spans {
+                writer.queue(span).map_err(located)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::Command;
+
+    use crate::{LocatedError, compile, generator::CrosstermCommandsGenerator};
+
+    #[test]
+    fn test_same_as_compile() {
+        let source = "<bg:blue one <green,b two>> \\<three\\>\n\n<208 four>";
+
+        let mut expected = String::new();
+        for span in compile::<CrosstermCommandsGenerator>(source).unwrap() {
+            span.write_ansi(&mut expected).unwrap();
+        }
+
+        let mut output = String::new();
+        <CrosstermCommandsGenerator>::default()
+            .compile_to_fmt_writer(source, &mut output)
+            .unwrap();
+        assert_eq!(output, expected);
+
+        let mut output = Vec::new();
+        <CrosstermCommandsGenerator>::default()
+            .compile_to_writer(source, &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let mut output = String::new();
+        <CrosstermCommandsGenerator>::default()
+            .compile_to_fmt_writer("\x1b[2J\u{9b}2J", &mut output)
+            .unwrap();
+        assert_eq!(output, "^[[2J\\u{9b}2J");
+    }
+
+    #[test]
+    fn test_error_location() {
+        let source = "one\ntwo <b three";
+
+        let mut output = String::new();
+        let error = <CrosstermCommandsGenerator>::default()
+            .compile_to_fmt_writer(source, &mut output)
+            .unwrap_err();
+        assert_eq!(error.location(), (2, 5));
+        assert_eq!(output, "one");
+
+        let error = <CrosstermCommandsGenerator>::default()
+            .compile_to_writer(source, Vec::new())
+            .unwrap_err();
+        assert_eq!(error.location(), (2, 5));
+    }
+}
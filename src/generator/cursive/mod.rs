@@ -0,0 +1,176 @@
+//! Generator implementations for cursive crate.
+
+mod span;
+mod tag;
+#[cfg(test)]
+mod test;
+
+use cursive_core::{style::Style, utils::markup::StyledString};
+pub use tag::CursiveTagConvertor;
+
+use crate::{
+    generator::{
+        Generator,
+        helper::{
+            CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize, SourceMap,
+            SourceMapGenerator, flatten_into, flatten_merged_into,
+        },
+    },
+    parser::{ItemC, ItemG},
+};
+
+/// Generator for `cursive` crate's [`StyledString`] type.
+///
+/// See [docs/cursive-tags.ebnf] for supported tags.
+///
+/// ## Example
+///
+/// ```
+/// use cursive_core::{
+///     style::{BaseColor, Style},
+///     utils::markup::StyledString,
+/// };
+/// use tui_markup::{compile, generator::CursiveStyledStringGenerator};
+///
+/// let mut expected = StyledString::single_span("I have a ", Style::none());
+/// expected.append_styled("green text", BaseColor::Green.dark());
+///
+/// assert_eq!(
+///     compile::<CursiveStyledStringGenerator>("I have a <green green text>"),
+///     Ok(expected),
+/// );
+/// ```
+///
+/// ### With custom tags
+///
+/// ```
+/// use cursive_core::style::{BaseColor, ColorStyle, Effect, Style};
+/// use tui_markup::{compile_with, generator::CursiveStyledStringGenerator};
+///
+/// let g = CursiveStyledStringGenerator::new(|tag: &str| match tag {
+///     "keyboard" => Some(
+///         Style::from(ColorStyle::new(
+///             BaseColor::Green.dark(),
+///             BaseColor::White.light(),
+///         ))
+///         .combine(Effect::Bold),
+///     ),
+///     _ => None,
+/// });
+///
+/// let result = compile_with("Press <keyboard W> to move up", g).unwrap();
+///
+/// assert_eq!(result.source(), "Press W to move up");
+/// ```
+///
+/// ### Show output
+///
+/// Use any view of the `cursive` crate that accepts a [`StyledString`], for example:
+/// `cursive::views::TextView`.
+///
+/// Lines of the markup source are joined with `\n` in the result.
+///
+/// [docs/cursive-tags.ebnf]: https://github.com/7sDream/tui-markup/blob/master/docs/cursive-tags.ebnf
+#[derive(Debug)]
+pub struct CursiveStyledStringGenerator<P = NoopCustomTagParser<Style>> {
+    convertor: CursiveTagConvertor<P>,
+    merge_spans: bool,
+    sanitize: Sanitize,
+}
+
+impl<P> Default for CursiveStyledStringGenerator<P> {
+    fn default() -> Self {
+        Self {
+            convertor: CursiveTagConvertor::<P>::default(),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
+        }
+    }
+}
+
+impl<P> CursiveStyledStringGenerator<P> {
+    /// Create a new generator, with a custom tag parser.
+    pub fn new(p: P) -> Self {
+        Self {
+            convertor: CursiveTagConvertor::new(p),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
+        }
+    }
+
+    /// Merge adjacent spans with same style into one, including text split by escapes. Disabled by
+    /// default.
+    ///
+    /// See [`flatten_merged`][super::helper::flatten_merged] for details.
+    #[must_use]
+    pub fn merge_spans(mut self, merge: bool) -> Self {
+        self.merge_spans = merge;
+        self
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.sanitize = policy;
+        self
+    }
+}
+
+impl<P> CursiveStyledStringGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn build<'a>(
+        &self, markup: Vec<Vec<ItemC<'a, CursiveTagConvertor<P>>>>,
+        mut map: Option<&mut SourceMap<'a>>,
+    ) -> StyledString {
+        let mut result = StyledString::new();
+        let mut spans: Vec<StyledString> = vec![];
+        for (i, line) in markup.into_iter().enumerate() {
+            if i > 0 {
+                result.append_styled("\n", Style::none());
+                if let Some(map) = map.as_deref_mut() {
+                    map.push_span([]);
+                }
+            }
+            if self.merge_spans {
+                flatten_merged_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            } else {
+                flatten_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            }
+            for span in spans.drain(..) {
+                result.append(span);
+            }
+        }
+        result
+    }
+}
+
+impl<'a, P> Generator<'a> for CursiveStyledStringGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    type Convertor = CursiveTagConvertor<P>;
+    type Err = GeneratorInfallible;
+    type Output = StyledString;
+
+    fn convertor(&mut self) -> &mut Self::Convertor {
+        &mut self.convertor
+    }
+
+    fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, None))
+    }
+}
+
+impl<'a, P> SourceMapGenerator<'a> for CursiveStyledStringGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, Some(map)))
+    }
+}
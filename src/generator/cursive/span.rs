@@ -0,0 +1,58 @@
+use std::borrow::Cow;
+
+use cursive_core::{
+    style::{Color, ColorStyle, Effect, EffectStatus, Effects, Style},
+    utils::markup::StyledString,
+};
+
+use crate::generator::{
+    Tag, TagConvertor,
+    helper::{FlattenableCowSpan, FlattenableSpan, FlattenableStyle, IntoOwned},
+};
+
+impl<'a, C> From<Tag<'a, C>> for Style
+where
+    C: TagConvertor<'a, Color = Color, Modifier = Effect, Custom = Style>,
+{
+    fn from(t: Tag<'a, C>) -> Self {
+        match t {
+            Tag::Fg(c) => ColorStyle::front(c).into(),
+            Tag::Bg(c) => ColorStyle::back(c).into(),
+            Tag::Modifier(e) => {
+                // `Effects::only` toggles the effect relative to the parent, which makes
+                // `<b <b x>>` not bold. Markup modifiers are additive, so force it on instead.
+                let mut effects = Effects::empty();
+                effects[e] = EffectStatus::On;
+                effects.into()
+            }
+            Tag::Custom(style) => style,
+        }
+    }
+}
+
+impl FlattenableStyle for Style {
+    fn patch(self, other: Self) -> Self {
+        self.combine(other)
+    }
+}
+
+impl<'a> FlattenableSpan<'a, Style> for StyledString {
+    fn with_style(s: &'a str, style: Option<Style>) -> Self {
+        StyledString::single_span(s, style.unwrap_or_else(Style::none))
+    }
+}
+
+impl<'a> FlattenableCowSpan<'a, Style> for StyledString {
+    fn with_style_cow(s: Cow<'a, str>, style: Option<Style>) -> Self {
+        StyledString::single_span(s, style.unwrap_or_else(Style::none))
+    }
+}
+
+/// [`StyledString`] owns its text already.
+impl IntoOwned for StyledString {
+    type Owned = Self;
+
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+}
@@ -0,0 +1,82 @@
+use cursive_core::style::{BaseColor, Color, Effect, Style};
+
+use crate::{
+    generator::{
+        TagConvertor,
+        helper::{CustomTagParser, NoopCustomTagParser},
+    },
+    parser::hex_rgb,
+};
+
+/// Tag convertor for [`CursiveStyledStringGenerator`](super::CursiveStyledStringGenerator).
+#[derive(Debug)]
+pub struct CursiveTagConvertor<P = NoopCustomTagParser<Style>> {
+    custom_tag_parser: Option<P>,
+}
+
+impl<P> Default for CursiveTagConvertor<P> {
+    fn default() -> Self {
+        Self {
+            custom_tag_parser: None,
+        }
+    }
+}
+
+impl<P> CursiveTagConvertor<P> {
+    /// Create a new tag convertor with custom tag parser.
+    pub fn new(p: P) -> Self {
+        Self {
+            custom_tag_parser: Some(p),
+        }
+    }
+}
+
+impl<'a, P> TagConvertor<'a> for CursiveTagConvertor<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    type Color = Color;
+    type Custom = Style;
+    type Modifier = Effect;
+
+    fn parse_color(&mut self, s: &str) -> Option<Self::Color> {
+        Some(match s {
+            "black" => Color::Dark(BaseColor::Black),
+            "red" => Color::Dark(BaseColor::Red),
+            "green" => Color::Dark(BaseColor::Green),
+            "yellow" => Color::Dark(BaseColor::Yellow),
+            "blue" => Color::Dark(BaseColor::Blue),
+            "magenta" | "purple" => Color::Dark(BaseColor::Magenta),
+            "cyan" => Color::Dark(BaseColor::Cyan),
+            "gray" => Color::Dark(BaseColor::White),
+            "gray+" => Color::Light(BaseColor::Black),
+            "red-" => Color::Light(BaseColor::Red),
+            "green-" => Color::Light(BaseColor::Green),
+            "yellow-" => Color::Light(BaseColor::Yellow),
+            "blue-" => Color::Light(BaseColor::Blue),
+            "magenta-" | "purple-" => Color::Light(BaseColor::Magenta),
+            "cyan-" => Color::Light(BaseColor::Cyan),
+            "white" => Color::Light(BaseColor::White),
+            s => hex_rgb(s)
+                .map(|(r, g, b)| Color::Rgb(r, g, b))
+                .or_else(|| s.parse::<u8>().ok().map(Color::from_256colors))?,
+        })
+    }
+
+    fn parse_modifier(&mut self, s: &str) -> Option<Self::Modifier> {
+        Some(match s {
+            "b" => Effect::Bold,
+            "d" => Effect::Dim,
+            "i" => Effect::Italic,
+            "u" => Effect::Underline,
+            "r" => Effect::Reverse,
+            "sb" | "rb" => Effect::Blink,
+            "s" => Effect::Strikethrough,
+            _ => return None,
+        })
+    }
+
+    fn parse_custom_tag(&mut self, s: &str) -> Option<Self::Custom> {
+        self.custom_tag_parser.as_mut().and_then(|f| f.parse(s))
+    }
+}
@@ -0,0 +1,97 @@
+use cursive_core::{
+    style::{BaseColor, ColorStyle, Effect, EffectStatus, Effects, Style},
+    utils::markup::StyledString,
+};
+
+use crate::{compile, compile_with, generator::CursiveStyledStringGenerator};
+
+fn styled(spans: &[(&str, Style)]) -> StyledString {
+    let mut result = StyledString::new();
+    for (s, style) in spans {
+        result.append_styled(*s, *style);
+    }
+    result
+}
+
+fn effect(e: Effect) -> Style {
+    let mut effects = Effects::empty();
+    effects[e] = EffectStatus::On;
+    effects.into()
+}
+
+macro_rules! test_ok {
+    ($s:expr => $($text:expr, $style:expr);* $(;)?) => {
+        assert_eq!(
+            compile::<CursiveStyledStringGenerator>($s),
+            Ok(styled(&[$(($text, $style)),*])),
+        )
+    };
+}
+
+#[test]
+fn test_normal_element() {
+    test_ok!("<green xxx>" => "xxx", BaseColor::Green.dark().into());
+    test_ok!("<fg:red- xxx>" => "xxx", BaseColor::Red.light().into());
+    test_ok!("<bg:yellow xxx>" => "xxx", ColorStyle::back(BaseColor::Yellow.dark()).into());
+    test_ok!("<b xxx>" => "xxx", effect(Effect::Bold));
+    test_ok!("<mod:i xxx>" => "xxx", effect(Effect::Italic));
+}
+
+#[test]
+fn test_nested_element() {
+    test_ok!(
+        "<bg:blue one <green two>>" =>
+        "one ", ColorStyle::back(BaseColor::Blue.dark()).into();
+        "two", ColorStyle::new(BaseColor::Green.dark(), BaseColor::Blue.dark()).into();
+    );
+}
+
+#[test]
+fn test_nested_modifier_is_additive() {
+    let bold = compile::<CursiveStyledStringGenerator>("<b <b x>>").unwrap();
+    let plain = compile::<CursiveStyledStringGenerator>("<b x>").unwrap();
+    assert_eq!(bold.spans_raw()[0].attr, plain.spans_raw()[0].attr);
+}
+
+#[test]
+fn test_multi_line() {
+    test_ok!(
+        "<red a>\nb" =>
+        "a", BaseColor::Red.dark().into();
+        "\n", Style::none();
+        "b", Style::none();
+    );
+}
+
+#[test]
+fn test_custom_tag_element() {
+    let s = Style::from(ColorStyle::new(
+        BaseColor::Green.dark(),
+        BaseColor::Blue.dark(),
+    ))
+    .combine(Effect::Bold);
+    let g = CursiveStyledStringGenerator::new(|tag: &str| match tag {
+        "keyboard" => Some(s),
+        _ => None,
+    });
+    assert_eq!(compile_with("<keyboard W>", g), Ok(styled(&[("W", s)])));
+}
+
+#[test]
+fn test_invalid_element() {
+    test_ok!("<qwerty one>" => "one", Style::none());
+}
+
+#[test]
+fn test_source_map() {
+    let source = "<green a\\<b>\n<green c><green d>";
+    let generator = <CursiveStyledStringGenerator>::default().merge_spans(true);
+    let (result, map) = crate::compile_with_source_map(source, generator).unwrap();
+
+    assert_eq!(result.spans().count(), 3);
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+        Some(7..11),
+        None,
+        Some(20..30)
+    ]);
+}
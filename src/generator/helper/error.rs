@@ -1,7 +1,4 @@
-use std::{
-    error::Error,
-    fmt::{Debug, Display},
-};
+use core::fmt::{Debug, Display};
 
 use crate::LocatedError;
 
@@ -19,18 +16,19 @@ impl GeneratorInfallible {
 }
 
 impl Debug for GeneratorInfallible {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         GeneratorInfallible::panic()
     }
 }
 
 impl Display for GeneratorInfallible {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         GeneratorInfallible::panic()
     }
 }
 
-impl Error for GeneratorInfallible {}
+#[cfg(feature = "std")]
+impl std::error::Error for GeneratorInfallible {}
 
 impl LocatedError for GeneratorInfallible {
     fn location(&self) -> (usize, usize) {
@@ -43,3 +41,57 @@ impl From<GeneratorInfallible> for crate::Error<'_, GeneratorInfallible> {
         GeneratorInfallible::panic()
     }
 }
+
+/// Error of a writer, with the line of output being written when it happened.
+///
+/// Used by methods which write output into a writer line by line, like `compile_to_writer` of
+/// builtin generators, so their errors are located in the same way as parse errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteError<E> {
+    // Line number in source
+    line: usize,
+    error: E,
+}
+
+impl<E> WriteError<E> {
+    pub(crate) fn new(line: usize, error: E) -> Self {
+        Self { line, error }
+    }
+
+    pub(crate) fn with_error<F>(self, error: F) -> WriteError<F> {
+        WriteError {
+            line: self.line,
+            error,
+        }
+    }
+
+    /// Get the error of writer.
+    pub fn get_ref(&self) -> &E {
+        &self.error
+    }
+
+    /// Take the error of writer.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: Display> Display for WriteError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} when writing line {}", self.error, self.line + 1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for WriteError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Location is the start of the line.
+impl<E> LocatedError for WriteError<E> {
+    fn location(&self) -> (usize, usize) {
+        (self.line + 1, 1)
+    }
+}
@@ -0,0 +1,93 @@
+use alloc::{borrow::Cow, string::String};
+use core::fmt::{Display, Write};
+
+fn is_special(c: char) -> bool {
+    matches!(c, '<' | '>' | '\\')
+}
+
+/// Escape a plain string, so it can be used as plain text in markup source.
+///
+/// Every `<`, `>` and `\` will be prefixed with a `\`, it's the reverse operation of [unescape].
+///
+/// ## Example
+///
+/// ```
+/// # use tui_markup::generator::helper::escape;
+/// assert_eq!(escape("a<b>\\c"), "a\\<b\\>\\\\c");
+///
+/// // no allocation if nothing need to be escaped
+/// assert!(matches!(escape("abc"), std::borrow::Cow::Borrowed("abc")));
+/// ```
+///
+/// [unescape]: super::unescape
+#[must_use]
+pub fn escape(s: &str) -> Cow<'_, str> {
+    if !s.contains(is_special) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut result = String::with_capacity(s.len() + 4);
+    for c in s.chars() {
+        if is_special(c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    Cow::Owned(result)
+}
+
+/// A wrapper which escapes the [Display] result of inner value.
+///
+/// It's useful when you want to interpolate some untrusted value into markup source.
+///
+/// ## Example
+///
+/// ```
+/// # use tui_markup::generator::helper::Escaped;
+/// let name = "<script>";
+/// assert_eq!(format!("<b {}>", Escaped(name)), "<b \\<script\\>>");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Escaped<T>(pub T);
+
+impl<T: Display> Display for Escaped<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct EscapeWriter<'a, 'b>(&'a mut core::fmt::Formatter<'b>);
+
+        impl Write for EscapeWriter<'_, '_> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                self.0.write_str(&escape(s))
+            }
+        }
+
+        write!(EscapeWriter(f), "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::{Escaped, escape};
+    use crate::generator::helper::unescape;
+
+    #[test]
+    fn test_escape_then_unescape() {
+        for s in ["", "abc", "<", ">", "\\", "a\\<b\\", "<<>>\\\\"] {
+            assert_eq!(unescape(&escape(s)).collect::<String>(), s);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_escape_then_unescape_any(s in any::<String>()) {
+            prop_assert_eq!(unescape(&escape(&s)).collect::<String>(), s);
+        }
+    }
+
+    #[test]
+    fn test_escaped_display() {
+        assert_eq!(Escaped("1<2").to_string(), "1\\<2");
+        assert_eq!(format!("{}", Escaped(42)), "42");
+    }
+}
@@ -1,5 +1,11 @@
+use alloc::{borrow::Cow, vec, vec::Vec};
+use core::ops::Range;
+
 use crate::{
-    generator::{Tag, TagConvertor, helper::unescape},
+    generator::{
+        Tag, TagConvertor,
+        helper::{Sanitize, Segment, SourceMap, sanitize, source_map::offset_in, unescape},
+    },
     parser::{Item, ItemC},
 };
 
@@ -10,6 +16,12 @@ pub trait FlattenableStyle: Default + Clone {
     fn patch(self, other: Self) -> Self;
 }
 
+/// No style, for stream generators which don't care about styles, like
+/// [`Buffered`][crate::generator::Buffered].
+impl FlattenableStyle for () {
+    fn patch(self, (): Self) -> Self {}
+}
+
 /// Requirements trait for span to used in [`flatten`] function.
 pub trait FlattenableSpan<'a, S: FlattenableStyle> {
     /// Create a span from str and a optional style.
@@ -18,55 +30,23 @@ pub trait FlattenableSpan<'a, S: FlattenableStyle> {
     fn with_style(s: &'a str, style: Option<S>) -> Self;
 }
 
-fn plain_text<'a, R, S>(escaped: &'a str, style: Option<S>) -> Vec<R>
-where
-    R: FlattenableSpan<'a, S>,
-    S: FlattenableStyle,
-{
-    unescape(escaped)
-        .map(|s| R::with_style(s, style.clone()))
-        .collect()
+/// Requirements trait for span to used in [`flatten_merged`] function.
+pub trait FlattenableCowSpan<'a, S: FlattenableStyle> {
+    /// Create a span from a maybe owned str and a optional style.
+    ///
+    /// The str is owned only if multi pieces of text are merged into one.
+    fn with_style_cow(s: Cow<'a, str>, style: Option<S>) -> Self;
 }
 
-fn element<'a, C, R, S>(
-    tags: Vec<Tag<'a, C>>, children: Vec<ItemC<'a, C>>, style: Option<S>,
-) -> Vec<R>
-where
-    C: TagConvertor<'a>,
-    R: FlattenableSpan<'a, S>,
-    S: FlattenableStyle + From<Tag<'a, C>>,
-{
-    let style = tags
-        .into_iter()
-        .map(S::from)
-        .fold(style.unwrap_or_default(), S::patch);
-    items(children, Some(style))
-}
+/// A plain text piece with it's style, the most simple span type.
+pub(crate) struct Piece<'a, S>(pub(crate) &'a str, pub(crate) Option<S>);
 
-fn item<'a, C, R, S>(item: ItemC<'a, C>, style: Option<S>) -> Vec<R>
-where
-    C: TagConvertor<'a>,
-    R: FlattenableSpan<'a, S>,
-    S: FlattenableStyle + From<Tag<'a, C>>,
-{
-    match item {
-        Item::PlainText(t) => plain_text(t, style),
-        Item::Element(tags, children) => element(tags, children, style),
+impl<'a, S: FlattenableStyle> FlattenableSpan<'a, S> for Piece<'a, S> {
+    fn with_style(s: &'a str, style: Option<S>) -> Self {
+        Self(s, style)
     }
 }
 
-fn items<'a, C, R, S>(items: Vec<ItemC<'a, C>>, style: Option<S>) -> Vec<R>
-where
-    C: TagConvertor<'a>,
-    R: FlattenableSpan<'a, S>,
-    S: FlattenableStyle + From<Tag<'a, C>>,
-{
-    items
-        .into_iter()
-        .flat_map(|x| item(x, style.clone()).into_iter())
-        .collect()
-}
-
 /// Flatten a line of ast tree item into a vector of target spans.
 ///
 /// ## Why need this
@@ -88,11 +68,251 @@ where
 /// - You have a struct `Span` implements [`FlattenableSpan`] trait to store styled text span.
 ///
 /// Then this function can be used to convert `Vec<ItemC<C>>` into `Vec<Span>`.
+///
+/// Plain text is sanitized by the default [`Sanitize`] policy, use [`flatten_with`] for others.
 pub fn flatten<'a, C, R, S>(line: Vec<ItemC<'a, C>>) -> Vec<R>
 where
     C: TagConvertor<'a>,
     R: FlattenableSpan<'a, S>,
     S: FlattenableStyle + From<Tag<'a, C>>,
 {
-    items(line, None)
+    flatten_with(line, Sanitize::default())
+}
+
+/// Same as [`flatten`], but plain text is sanitized by the provided [`Sanitize`] policy.
+pub fn flatten_with<'a, C, R, S>(line: Vec<ItemC<'a, C>>, policy: Sanitize) -> Vec<R>
+where
+    C: TagConvertor<'a>,
+    R: FlattenableSpan<'a, S>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+{
+    let mut spans = vec![];
+    flatten_into(line, &mut spans, policy, None);
+    spans
+}
+
+/// Same as [`flatten_with`], and also push segments of each output span into a [`SourceMap`].
+///
+/// Text in `line` which is not a slice of source of the map, like text of a AST built by hand,
+/// has no mapping.
+pub fn flatten_mapped<'a, C, R, S>(
+    line: Vec<ItemC<'a, C>>, policy: Sanitize, map: &mut SourceMap<'a>,
+) -> Vec<R>
+where
+    C: TagConvertor<'a>,
+    R: FlattenableSpan<'a, S>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+{
+    let mut spans = vec![];
+    flatten_into(line, &mut spans, policy, Some(map));
+    spans
+}
+
+/// Call `f` with each sanitized piece of plain text in a line, it's style, and it's byte range in
+/// `source` if provided.
+fn for_each_piece<'a, C, S>(
+    line: Vec<ItemC<'a, C>>, policy: Sanitize, source: Option<&'a str>,
+    mut f: impl FnMut(&'a str, Option<Range<usize>>, &Option<S>),
+) where
+    C: TagConvertor<'a>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+{
+    // Remaining items of each open element, with the style of that element
+    let mut open: Vec<(vec::IntoIter<ItemC<'a, C>>, Option<S>)> = vec![(line.into_iter(), None)];
+
+    while let Some((items, style)) = open.last_mut() {
+        match items.next() {
+            None => {
+                open.pop();
+            }
+            Some(Item::PlainText(t)) => {
+                let base = source.and_then(|source| offset_in(source, t));
+                let mut unescaped = unescape(t);
+                loop {
+                    let offset = unescaped.offset();
+                    let Some(text) = unescaped.next() else {
+                        break;
+                    };
+                    let mut pieces = sanitize(text, policy);
+                    while let Some((range, piece)) = pieces.next_with_range() {
+                        let source = base.map(|b| b + offset + range.start..b + offset + range.end);
+                        f(piece, source, style);
+                    }
+                }
+            }
+            Some(Item::Element(tags, children)) => {
+                let style = tags
+                    .into_iter()
+                    .map(S::from)
+                    .fold(style.clone().unwrap_or_default(), S::patch);
+                open.push((children.into_iter(), Some(style)));
+            }
+        }
+    }
+}
+
+/// Same as [`flatten_with`], but push spans into an existing vector, and their segments into the
+/// map if provided.
+pub(crate) fn flatten_into<'a, C, R, S>(
+    line: Vec<ItemC<'a, C>>, spans: &mut Vec<R>, policy: Sanitize,
+    mut map: Option<&mut SourceMap<'a>>,
+) where
+    C: TagConvertor<'a>,
+    R: FlattenableSpan<'a, S>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+{
+    let source = map.as_deref().map(SourceMap::source);
+    for_each_piece(line, policy, source, |s, source, style| {
+        spans.push(R::with_style(s, style.clone()));
+        if let Some(map) = map.as_deref_mut() {
+            map.push_span(source.map(|source| Segment {
+                output: 0..s.len(),
+                source,
+            }));
+        }
+    });
+}
+
+/// Same as [`flatten`], but adjacent spans with same style are merged into one.
+///
+/// So text contains escapes, like `a\<b`, and adjacent elements with same style, like
+/// `<b x><b y>`, are all output as a single span. A top level text without style is treated as the
+/// same style as [`Default`] one.
+///
+/// The cost is that merged text must be copied into a owned [String], so they are not slice of
+/// markup source anymore.
+pub fn flatten_merged<'a, C, R, S>(line: Vec<ItemC<'a, C>>) -> Vec<R>
+where
+    C: TagConvertor<'a>,
+    R: FlattenableCowSpan<'a, S>,
+    S: FlattenableStyle + PartialEq + From<Tag<'a, C>>,
+{
+    flatten_merged_with(line, Sanitize::default())
+}
+
+/// Same as [`flatten_merged`], but plain text is sanitized by the provided [`Sanitize`] policy.
+pub fn flatten_merged_with<'a, C, R, S>(line: Vec<ItemC<'a, C>>, policy: Sanitize) -> Vec<R>
+where
+    C: TagConvertor<'a>,
+    R: FlattenableCowSpan<'a, S>,
+    S: FlattenableStyle + PartialEq + From<Tag<'a, C>>,
+{
+    let mut spans = vec![];
+    flatten_merged_into(line, &mut spans, policy, None);
+    spans
+}
+
+/// Same as [`flatten_merged_with`], and also push segments of each output span into a
+/// [`SourceMap`], a merged span has a segment for each piece of text joined into it.
+pub fn flatten_merged_mapped<'a, C, R, S>(
+    line: Vec<ItemC<'a, C>>, policy: Sanitize, map: &mut SourceMap<'a>,
+) -> Vec<R>
+where
+    C: TagConvertor<'a>,
+    R: FlattenableCowSpan<'a, S>,
+    S: FlattenableStyle + PartialEq + From<Tag<'a, C>>,
+{
+    let mut spans = vec![];
+    flatten_merged_into(line, &mut spans, policy, Some(map));
+    spans
+}
+
+/// Same as [`flatten_merged_with`], but push spans into an existing vector, and their segments
+/// into the map if provided.
+pub(crate) fn flatten_merged_into<'a, C, R, S>(
+    line: Vec<ItemC<'a, C>>, spans: &mut Vec<R>, policy: Sanitize, map: Option<&mut SourceMap<'a>>,
+) where
+    C: TagConvertor<'a>,
+    R: FlattenableCowSpan<'a, S>,
+    S: FlattenableStyle + PartialEq + From<Tag<'a, C>>,
+{
+    let same = |a: &Option<S>, b: &Option<S>| match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a == b,
+        (Some(s), None) | (None, Some(s)) => *s == S::default(),
+    };
+
+    let mut runs: Vec<(Cow<'a, str>, Option<S>, Vec<Segment>)> = vec![];
+    let source = map.as_deref().map(SourceMap::source);
+    for_each_piece(line, policy, source, |s, source, style: &Option<S>| {
+        let run = match runs.last_mut() {
+            Some(run) if same(&run.1, style) => {
+                run.0.to_mut().push_str(s);
+                run
+            }
+            _ => {
+                runs.push((Cow::Borrowed(s), style.clone(), vec![]));
+                runs.last_mut().expect("just pushed")
+            }
+        };
+        if let Some(source) = source {
+            let end = run.0.len();
+            run.2.push(Segment {
+                output: end - s.len()..end,
+                source,
+            });
+        }
+    });
+
+    match map {
+        Some(map) => spans.extend(runs.into_iter().map(|(s, style, segments)| {
+            map.push_span(segments);
+            R::with_style_cow(s, style)
+        })),
+        None => spans.extend(
+            runs.into_iter()
+                .map(|(s, style, _)| R::with_style_cow(s, style)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{borrow::Cow, vec, vec::Vec};
+
+    use super::{FlattenableCowSpan, FlattenableStyle, flatten_merged};
+    use crate::{
+        generator::{Tag, TagConvertor},
+        parser::parse,
+        test_util::Convertor,
+    };
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct Bold(bool);
+
+    impl FlattenableStyle for Bold {
+        fn patch(self, other: Self) -> Self {
+            Self(self.0 || other.0)
+        }
+    }
+
+    impl<'a> From<Tag<'a, Convertor>> for Bold {
+        fn from(_: Tag<'a, Convertor>) -> Self {
+            Self(true)
+        }
+    }
+
+    impl<'a> FlattenableCowSpan<'a, Bold> for (Cow<'a, str>, bool) {
+        fn with_style_cow(s: Cow<'a, str>, style: Option<Bold>) -> Self {
+            (s, style.unwrap_or_default().0)
+        }
+    }
+
+    #[test]
+    fn test_flatten_merged() {
+        let line = parse("a\\\\b<b c\\<><b d><x e><b <b f>>g")
+            .unwrap()
+            .remove(0);
+        let line = Convertor.convert_line(line);
+        let spans: Vec<(Cow<'_, str>, bool)> = flatten_merged(line);
+
+        assert_eq!(spans, vec![
+            (Cow::Owned("a\\b".to_owned()), false),
+            (Cow::Owned("c<d".to_owned()), true),
+            (Cow::Borrowed("e"), false),
+            (Cow::Borrowed("f"), true),
+            (Cow::Borrowed("g"), false),
+        ]);
+        assert!(matches!(spans[2].0, Cow::Borrowed(_)));
+    }
 }
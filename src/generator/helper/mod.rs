@@ -1,11 +1,42 @@
 //! Helper functions for create generator.
 
 mod error;
+mod escape;
 mod flatten;
+#[cfg(feature = "ansi")]
+mod io;
+mod owned;
+mod sanitize;
+#[cfg(any(feature = "ansi", feature = "crossterm"))]
+mod sgr;
+mod source_map;
 mod tag;
+mod tree;
 mod unescape;
 
-pub use error::GeneratorInfallible;
-pub use flatten::{FlattenableSpan, FlattenableStyle, flatten};
+pub use error::{GeneratorInfallible, WriteError};
+pub use escape::{Escaped, escape};
+pub(crate) use flatten::Piece;
+pub use flatten::{
+    FlattenableCowSpan, FlattenableSpan, FlattenableStyle, flatten, flatten_mapped, flatten_merged,
+    flatten_merged_mapped, flatten_merged_with, flatten_with,
+};
+#[cfg(any(
+    feature = "ansi",
+    feature = "console",
+    feature = "crossterm",
+    feature = "cursive",
+    feature = "nu-ansi-term",
+    feature = "ratatui"
+))]
+pub(crate) use flatten::{flatten_into, flatten_merged_into};
+#[cfg(feature = "ansi")]
+pub(crate) use io::IoWriter;
+pub use owned::IntoOwned;
+pub use sanitize::{Sanitize, Sanitized, sanitize};
+#[cfg(any(feature = "ansi", feature = "crossterm"))]
+pub(crate) use sgr::SgrParams;
+pub use source_map::{Segment, SourceMap, SourceMapGenerator};
 pub use tag::{CustomTagParser, NoopCustomTagParser};
+pub use tree::{TreeVisitor, walk, walk_converted};
 pub use unescape::{Unescape, unescape};
@@ -0,0 +1,10 @@
+/// Output of a generator which can be converted into a version that does not borrow markup source.
+///
+/// Used by [`CompileCache`][crate::cache::CompileCache] to keep outputs after source is gone.
+pub trait IntoOwned {
+    /// Owned output type.
+    type Owned: 'static;
+
+    /// Convert into owned output, copy all borrowed text.
+    fn into_owned(self) -> Self::Owned;
+}
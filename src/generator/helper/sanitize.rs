@@ -0,0 +1,230 @@
+use core::ops::Range;
+
+/// How to output control characters and bidi overrides in plain text.
+///
+/// Plain text may come from untrusted input, like user names or chat messages. Raw escape sequences
+/// in it, like `\x1b[2J` or a OSC 52 clipboard write, are executed by the terminal when printed,
+/// and bidi overrides make the displayed text different from its content.
+///
+/// Unsafe characters are:
+///
+/// - C0 control characters except tab, and DEL: `U+0000..=U+001F`, `U+007F`.
+/// - C1 control characters: `U+0080..=U+009F`.
+/// - Bidi embeddings, overrides and isolates: `U+202A..=U+202E`, `U+2066..=U+2069`.
+///
+/// All builtin generators use [`Sanitize::Replace`] by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sanitize {
+    /// Output unsafe characters as is, only for trusted source.
+    Keep,
+    /// Remove unsafe characters.
+    Strip,
+    /// Replace unsafe characters with visible forms, `^[` for ESC, `^?` for DEL, and `\u{9b}` like
+    /// forms for others.
+    #[default]
+    Replace,
+}
+
+const C0: [&str; 32] = [
+    "^@", "^A", "^B", "^C", "^D", "^E", "^F", "^G", "^H", "^I", "^J", "^K", "^L", "^M", "^N", "^O",
+    "^P", "^Q", "^R", "^S", "^T", "^U", "^V", "^W", "^X", "^Y", "^Z", "^[", "^\\", "^]", "^^",
+    "^_",
+];
+
+const C1: [&str; 32] = [
+    "\\u{80}", "\\u{81}", "\\u{82}", "\\u{83}", "\\u{84}", "\\u{85}", "\\u{86}", "\\u{87}",
+    "\\u{88}", "\\u{89}", "\\u{8a}", "\\u{8b}", "\\u{8c}", "\\u{8d}", "\\u{8e}", "\\u{8f}",
+    "\\u{90}", "\\u{91}", "\\u{92}", "\\u{93}", "\\u{94}", "\\u{95}", "\\u{96}", "\\u{97}",
+    "\\u{98}", "\\u{99}", "\\u{9a}", "\\u{9b}", "\\u{9c}", "\\u{9d}", "\\u{9e}", "\\u{9f}",
+];
+
+/// Visible form of a unsafe character, `None` if it's safe.
+fn replacement(c: char) -> Option<&'static str> {
+    Some(match c {
+        '\t' => return None,
+        '\0'..='\x1f' => C0[c as usize],
+        '\x7f' => "^?",
+        '\u{80}'..='\u{9f}' => C1[c as usize - 0x80],
+        '\u{202a}' => "\\u{202a}",
+        '\u{202b}' => "\\u{202b}",
+        '\u{202c}' => "\\u{202c}",
+        '\u{202d}' => "\\u{202d}",
+        '\u{202e}' => "\\u{202e}",
+        '\u{2066}' => "\\u{2066}",
+        '\u{2067}' => "\\u{2067}",
+        '\u{2068}' => "\\u{2068}",
+        '\u{2069}' => "\\u{2069}",
+        _ => return None,
+    })
+}
+
+/// First unsafe character in `s`, with its byte offset.
+fn find_unsafe(s: &str) -> Option<(usize, char, &'static str)> {
+    let bytes = s.as_bytes();
+    let mut start = 0;
+
+    // All unsafe characters are ASCII, or start with byte 0xC2 or 0xE2 in UTF-8
+    while let Some(offset) = bytes[start..]
+        .iter()
+        .position(|&b| b < 0x20 || matches!(b, 0x7f | 0xc2 | 0xe2))
+    {
+        let pos = start + offset;
+        let c = s[pos..].chars().next()?;
+        if let Some(visible) = replacement(c) {
+            return Some((pos, c, visible));
+        }
+        start = pos + 1;
+    }
+
+    None
+}
+
+/// Sanitize unescaped plain text by a [`Sanitize`] policy, into a iterator of strings.
+///
+/// Safe parts are returned as slices of the input, so text without any unsafe character is
+/// returned as a whole.
+///
+/// ## Example
+///
+/// ```
+/// # use tui_markup::generator::helper::{Sanitize, sanitize};
+/// let text = "a\x1b[2Jb\u{202e}c";
+///
+/// assert_eq!(sanitize(text, Sanitize::Keep).collect::<Vec<_>>(), vec![
+///     text
+/// ]);
+/// assert_eq!(
+///     sanitize(text, Sanitize::Strip).collect::<String>(),
+///     "a[2Jbc"
+/// );
+/// assert_eq!(
+///     sanitize(text, Sanitize::Replace).collect::<String>(),
+///     "a^[[2Jb\\u{202e}c"
+/// );
+/// ```
+#[must_use]
+pub fn sanitize(text: &str, policy: Sanitize) -> Sanitized<'_> {
+    Sanitized {
+        text,
+        cursor: 0,
+        policy,
+    }
+}
+
+/// Iterator type for [sanitize].
+#[derive(Debug)]
+pub struct Sanitized<'a> {
+    text: &'a str,
+    cursor: usize,
+    policy: Sanitize,
+}
+
+impl<'a> Sanitized<'a> {
+    /// Next piece, with byte range of the input text it comes from.
+    ///
+    /// A replacement comes from the unsafe char it replaces.
+    pub(crate) fn next_with_range(&mut self) -> Option<(Range<usize>, &'a str)> {
+        loop {
+            let start = self.cursor;
+            let rest = &self.text[start..];
+            if rest.is_empty() {
+                return None;
+            }
+
+            let found = match self.policy {
+                Sanitize::Keep => None,
+                Sanitize::Strip | Sanitize::Replace => find_unsafe(rest),
+            };
+
+            match found {
+                None => {
+                    self.cursor = self.text.len();
+                    return Some((start..self.cursor, rest));
+                }
+                Some((0, c, visible)) => {
+                    self.cursor += c.len_utf8();
+                    if self.policy == Sanitize::Replace {
+                        return Some((start..self.cursor, visible));
+                    }
+                }
+                Some((offset, ..)) => {
+                    self.cursor += offset;
+                    return Some((start..self.cursor, &rest[..offset]));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Sanitized<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_range().map(|(_, s)| s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use super::{Sanitize, sanitize};
+
+    fn collect(text: &str, policy: Sanitize) -> Vec<&str> {
+        sanitize(text, policy).collect()
+    }
+
+    #[test]
+    fn test_safe_text_is_not_split() {
+        for policy in [Sanitize::Keep, Sanitize::Strip, Sanitize::Replace] {
+            assert_eq!(collect("a\tb 中文 é ¢ ‐", policy), vec![
+                "a\tb 中文 é ¢ ‐"
+            ]);
+            assert!(collect("", policy).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_replace() {
+        assert_eq!(collect("\x1b]52;c;eA==\x07", Sanitize::Replace), vec![
+            "^[",
+            "]52;c;eA==",
+            "^G"
+        ]);
+        assert_eq!(collect("\0\r\x1f\x7f", Sanitize::Replace), vec![
+            "^@", "^M", "^_", "^?"
+        ]);
+        assert_eq!(
+            collect("\u{9b}2J\u{2066}\u{202a}x\u{2069}", Sanitize::Replace),
+            vec!["\\u{9b}", "2J", "\\u{2066}", "\\u{202a}", "x", "\\u{2069}"]
+        );
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(collect("\x1b[2J\x1b[1mhi\u{85}", Sanitize::Strip), vec![
+            "[2J", "[1mhi"
+        ]);
+        assert!(collect("\x1b\u{202e}", Sanitize::Strip).is_empty());
+    }
+
+    #[test]
+    fn test_all_chars() {
+        for c in '\0'..='\u{3000}' {
+            let is_unsafe = matches!(c, '\0'..='\x08' | '\n'..='\x1f' | '\x7f'..='\u{9f}')
+                || matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}');
+            let text = String::from(c);
+            let visible = collect(&text, Sanitize::Replace).concat();
+
+            if is_unsafe {
+                assert!(collect(&text, Sanitize::Strip).is_empty(), "{c:?}");
+                assert!(
+                    visible.starts_with('^') || visible.starts_with("\\u{"),
+                    "{c:?}"
+                );
+            } else {
+                assert_eq!(visible, text);
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt::{self, Display, Write};
+
+/// Parameters of SGR sequences, collected to be written as a single sequence.
+#[derive(Debug, Default)]
+pub(crate) struct SgrParams(String);
+
+impl SgrParams {
+    /// Add a parameter, like `22`.
+    pub(crate) fn push(&mut self, param: impl Display) {
+        if !self.0.is_empty() {
+            self.0.push(';');
+        }
+        // Writing into a String never fails
+        let _ = write!(self.0, "{param}");
+    }
+
+    /// Add parameters of rendered SGR sequences, like `1` and `32` of `\x1b[1m\x1b[32m`.
+    pub(crate) fn push_rendered(&mut self, rendered: &str) {
+        rendered
+            .split("\x1b[")
+            .filter_map(|seq| seq.strip_suffix('m'))
+            .filter(|param| !param.is_empty())
+            .for_each(|param| self.push(param));
+    }
+
+    /// Write all parameters as a single SGR sequence, nothing if there is no parameter.
+    pub(crate) fn write_to(&self, f: &mut impl Write) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        write!(f, "\x1b[{}m", self.0)
+    }
+}
@@ -0,0 +1,260 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{generator::Generator, parser::ItemG};
+
+/// A part of a output span, and where it comes from in markup source.
+///
+/// A plain text span has only one segment. A merged span has a segment for each piece of text
+/// joined into it, so text split by escapes is still mapped to it's real position.
+///
+/// The output and source ranges have the same length, except for visible replacements of unsafe
+/// chars by [`Sanitize::Replace`][super::Sanitize::Replace], which map to the whole replaced char.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// Byte range in text of the output span.
+    pub output: Range<usize>,
+    /// Byte range in markup source.
+    pub source: Range<usize>,
+}
+
+impl Segment {
+    fn is_replacement(&self) -> bool {
+        self.output.len() != self.source.len()
+    }
+}
+
+/// Generator which records a [`SourceMap`] of it's output spans while generating.
+///
+/// Implemented by builtin generators whose output is a series of spans, see
+/// [`compile_with_source_map`][crate::compile_with_source_map].
+///
+/// If your generator uses [`flatten_with`][super::flatten_with] or
+/// [`flatten_merged_with`][super::flatten_merged_with], use
+/// [`flatten_mapped`][super::flatten_mapped]
+/// or [`flatten_merged_mapped`][super::flatten_merged_mapped] instead to implement this trait.
+pub trait SourceMapGenerator<'a>: Generator<'a> {
+    /// Same as [`Generator::generate`], and also push segments of each output span into the
+    /// map, in output order.
+    ///
+    /// ## Errors
+    ///
+    /// Same as [`Generator::generate`].
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err>;
+}
+
+impl<'a, G: SourceMapGenerator<'a>> SourceMapGenerator<'a> for &mut G {
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, G>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        <G as SourceMapGenerator<'a>>::generate_with_source_map(self, markup, map)
+    }
+}
+
+/// Byte offset of a text in markup source, if it's a slice of source.
+///
+/// Plain text in AST from the parser is always a slice of source.
+pub(crate) fn offset_in(source: &str, text: &str) -> Option<usize> {
+    let start = (text.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    (start + text.len() <= source.len()).then_some(start)
+}
+
+/// Map from output spans of a generator back to byte offsets in markup source.
+///
+/// Ranges are recorded while spans are built, so merged spans and sanitized text are mapped too.
+/// Spans not come from source, like line breaks inserted by some generators, have no mapping.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ansi")] {
+/// use tui_markup::{compile_with_source_map, generator::ANSIStringsGenerator};
+///
+/// let source = "<green a\\<b>";
+/// let (text, map) = compile_with_source_map(source, <ANSIStringsGenerator>::default()).unwrap();
+///
+/// assert_eq!(text.spans().len(), 2);
+/// assert_eq!(map.span(0), Some(7..8)); // "a"
+/// assert_eq!(map.span(1), Some(9..11)); // "<b", after the escape char
+/// // "b" in source
+/// assert_eq!(map.char_offset(1, 1), Some(10));
+///
+/// let generator = <ANSIStringsGenerator>::default().merge_spans(true);
+/// let (text, map) = compile_with_source_map(source, generator).unwrap();
+///
+/// assert_eq!(text.spans().len(), 1);
+/// assert_eq!(map.span(0), Some(7..11)); // "a<b"
+/// // "<" of the merged span
+/// assert_eq!(map.byte_offset(0, 1), Some(9));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    segments: Vec<Segment>,
+    // End index in segments of each span
+    ends: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Create an empty source map of markup source.
+    #[must_use]
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            segments: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Add a output span with it's segments, which should be in output order.
+    ///
+    /// Use an empty iterator for spans not come from source.
+    pub fn push_span(&mut self, segments: impl IntoIterator<Item = Segment>) {
+        self.segments.extend(segments);
+        self.ends.push(self.segments.len());
+    }
+
+    /// Markup source of this map.
+    #[must_use]
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Count of output spans.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// If there is no output span.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Segments of a output span, empty if index out of range or the span does not come from
+    /// source.
+    #[must_use]
+    pub fn segments(&self, index: usize) -> &[Segment] {
+        let Some(&end) = self.ends.get(index) else {
+            return &[];
+        };
+        let start = index.checked_sub(1).map_or(0, |i| self.ends[i]);
+        &self.segments[start..end]
+    }
+
+    /// Source byte range of a output span, from start of it's first segment to end of the last.
+    ///
+    /// Returns `None` if index out of range or the span does not come from source.
+    #[must_use]
+    pub fn span(&self, index: usize) -> Option<Range<usize>> {
+        let segments = self.segments(index);
+        Some(segments.first()?.source.start..segments.last()?.source.end)
+    }
+
+    /// Source byte offset of a byte offset inside a output span.
+    ///
+    /// A byte inside a visible replacement maps to the start of the replaced char.
+    #[must_use]
+    pub fn byte_offset(&self, index: usize, offset: usize) -> Option<usize> {
+        let segment = self
+            .segments(index)
+            .iter()
+            .find(|s| s.output.contains(&offset))?;
+        if segment.is_replacement() {
+            Some(segment.source.start)
+        } else {
+            Some(segment.source.start + offset - segment.output.start)
+        }
+    }
+
+    /// Source byte offset of the nth char inside a output span.
+    ///
+    /// Returns `None` if the span has text not come from source before that char.
+    #[must_use]
+    pub fn char_offset(&self, index: usize, mut nth: usize) -> Option<usize> {
+        let mut cursor = 0;
+        for segment in self.segments(index) {
+            if segment.output.start != cursor {
+                return None;
+            }
+            cursor = segment.output.end;
+
+            // Visible replacements are all ASCII
+            let count = if segment.is_replacement() {
+                if nth < segment.output.len() {
+                    return Some(segment.source.start);
+                }
+                segment.output.len()
+            } else {
+                let mut chars = self.source[segment.source.clone()].char_indices();
+                if let Some((offset, _)) = chars.nth(nth) {
+                    return Some(segment.source.start + offset);
+                }
+                self.source[segment.source.clone()].chars().count()
+            };
+            nth -= count;
+        }
+        None
+    }
+
+    /// Iterate over source byte ranges of all output spans.
+    pub fn iter(&self) -> impl Iterator<Item = Option<Range<usize>>> + '_ {
+        (0..self.len()).map(|i| self.span(i))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{vec, vec::Vec};
+
+    use super::{Segment, SourceMap};
+
+    fn segment(output: (usize, usize), source: (usize, usize)) -> Segment {
+        Segment {
+            output: output.0..output.1,
+            source: source.0..source.1,
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        let mut map = SourceMap::new("ab\\\\cd\\<");
+        map.push_span([segment((0, 2), (0, 2))]);
+        map.push_span([]);
+        map.push_span([segment((0, 3), (3, 6)), segment((3, 4), (7, 8))]);
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+            Some(0..2),
+            None,
+            Some(3..8)
+        ]);
+        assert_eq!(map.byte_offset(2, 2), Some(5));
+        assert_eq!(map.byte_offset(2, 3), Some(7));
+        assert_eq!(map.byte_offset(2, 4), None);
+        assert_eq!(map.char_offset(1, 0), None);
+        assert_eq!(map.char_offset(2, 3), Some(7));
+        assert!(map.segments(3).is_empty());
+    }
+
+    #[test]
+    fn test_char_offset() {
+        let mut map = SourceMap::new("<b 中\x1b文>");
+        map.push_span([
+            segment((0, 3), (3, 6)),
+            segment((3, 5), (6, 7)),
+            segment((5, 8), (7, 10)),
+        ]);
+
+        assert_eq!(map.char_offset(0, 0), Some(3));
+        assert_eq!(map.char_offset(0, 1), Some(6));
+        assert_eq!(map.char_offset(0, 2), Some(6));
+        assert_eq!(map.char_offset(0, 3), Some(7));
+        assert_eq!(map.char_offset(0, 4), None);
+        assert_eq!(map.byte_offset(0, 4), Some(6));
+    }
+}
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// A common trait for user to provide custom tag parser to a tag convertor.
 ///
@@ -36,6 +36,14 @@ where
 #[derive(Debug)]
 pub struct NoopCustomTagParser<S>(PhantomData<fn() -> S>);
 
+impl<S> Clone for NoopCustomTagParser<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for NoopCustomTagParser<S> {}
+
 impl<S> Default for NoopCustomTagParser<S> {
     fn default() -> Self {
         Self(PhantomData)
@@ -0,0 +1,213 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    generator::{
+        Tag, TagConvertor,
+        helper::{FlattenableStyle, Sanitize, sanitize, unescape},
+    },
+    parser::{Item, ItemC},
+};
+
+/// Event handler trait for [`walk`] and [`walk_converted`] function.
+///
+/// Unlike [`flatten`][super::flatten], which reduces each element into a style directly, this
+/// keeps the nesting structure, so it's useful for backends like HTML `<span>` or Pango markup.
+///
+/// The `inherited` style given to every hook is the style of enclosing elements, all patched
+/// together. It does not contain tags of the current element.
+pub trait TreeVisitor<'a, C: TagConvertor<'a>, S> {
+    /// Called before children of a element are visited.
+    ///
+    /// `raw` contains all raw tag strings, `tags` contains tags which are successfully converted by
+    /// the convertor.
+    fn enter_element(&mut self, raw: &[&'a str], tags: &[Tag<'a, C>], inherited: &S);
+
+    /// Called after children of a element are visited.
+    fn exit_element(&mut self, raw: &[&'a str], inherited: &S);
+
+    /// Called for each unescaped plain text, `style` is style of all enclosing elements.
+    fn text(&mut self, text: &'a str, style: &S);
+
+    /// Policy for control characters and bidi overrides in text given to [`text`][Self::text],
+    /// [`Sanitize::Replace`] by default.
+    fn sanitize(&self) -> Sanitize {
+        Sanitize::default()
+    }
+}
+
+fn items<'a, T, C, S, V>(
+    items: Vec<Item<'a, T>>, inherited: &S,
+    split: &mut impl FnMut(Vec<T>) -> (Vec<&'a str>, Vec<Tag<'a, C>>), visitor: &mut V,
+) where
+    C: TagConvertor<'a>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+    V: TreeVisitor<'a, C, S> + ?Sized,
+{
+    for item in items {
+        match item {
+            Item::PlainText(t) => {
+                let policy = visitor.sanitize();
+                unescape(t)
+                    .flat_map(|s| sanitize(s, policy))
+                    .for_each(|s| visitor.text(s, inherited));
+            }
+            Item::Element(tags, children) => {
+                let (raw, tags) = split(tags);
+                visitor.enter_element(&raw, &tags, inherited);
+                let style = tags
+                    .into_iter()
+                    .map(S::from)
+                    .fold(inherited.clone(), S::patch);
+                self::items(children, &style, split, visitor);
+                visitor.exit_element(&raw, inherited);
+            }
+        }
+    }
+}
+
+/// Walk a line of raw ast items, convert tags by the convertor, and send events to the visitor.
+///
+/// Because tags are converted on the fly, raw tag strings are also available in events. It can be
+/// used on result of [`parser::parse`][crate::parser::parse] directly.
+///
+/// The style type `S` has same requirements as the [`flatten`][super::flatten] function.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ansi")] {
+/// use anstyle::Style;
+/// use tui_markup::{
+///     generator::{
+///         Tag,
+///         ansi::ANSITagConvertor,
+///         helper::{TreeVisitor, walk},
+///     },
+///     parser::parse,
+/// };
+///
+/// #[derive(Default)]
+/// struct Html(String);
+///
+/// impl<'a> TreeVisitor<'a, ANSITagConvertor, Style> for Html {
+///     fn enter_element(&mut self, raw: &[&'a str], _: &[Tag<'a, ANSITagConvertor>], _: &Style) {
+///         self.0
+///             .push_str(&format!("<span class=\"{}\">", raw.join(" ")));
+///     }
+///
+///     fn exit_element(&mut self, _: &[&'a str], _: &Style) {
+///         self.0.push_str("</span>");
+///     }
+///
+///     fn text(&mut self, text: &'a str, _: &Style) {
+///         self.0.push_str(text);
+///     }
+/// }
+///
+/// let mut html = Html::default();
+/// for line in parse("<b hello <green world>>").unwrap() {
+///     walk(line, &mut ANSITagConvertor::default(), &mut html);
+/// }
+/// assert_eq!(
+///     html.0,
+///     "<span class=\"b\">hello <span class=\"green\">world</span></span>"
+/// );
+/// # }
+/// ```
+pub fn walk<'a, C, S, V>(line: Vec<Item<'a>>, convertor: &mut C, visitor: &mut V)
+where
+    C: TagConvertor<'a>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+    V: TreeVisitor<'a, C, S> + ?Sized,
+{
+    let mut split = |raw: Vec<&'a str>| {
+        let tags = raw
+            .iter()
+            .filter_map(|s| convertor.convert_tag(s))
+            .collect();
+        (raw, tags)
+    };
+    items(line, &S::default(), &mut split, visitor);
+}
+
+/// Walk a line of ast items after tag conversion stage, and send events to the visitor.
+///
+/// This can be used in [`Generator::generate`][crate::generator::Generator::generate], but raw tag
+/// strings are not available anymore, the `raw` argument of events is always empty.
+pub fn walk_converted<'a, C, S, V>(line: Vec<ItemC<'a, C>>, visitor: &mut V)
+where
+    C: TagConvertor<'a>,
+    S: FlattenableStyle + From<Tag<'a, C>>,
+    V: TreeVisitor<'a, C, S> + ?Sized,
+{
+    items(line, &S::default(), &mut |tags| (vec![], tags), visitor);
+}
+
+#[cfg(all(test, feature = "ansi"))]
+mod test {
+    use anstyle::{AnsiColor, Style};
+
+    use super::{TreeVisitor, walk, walk_converted};
+    use crate::{
+        generator::{Tag, TagConvertor, ansi::ANSITagConvertor},
+        parser::parse,
+    };
+
+    #[derive(Default)]
+    struct Events(Vec<String>);
+
+    impl<'a> TreeVisitor<'a, ANSITagConvertor, Style> for Events {
+        fn enter_element(
+            &mut self, raw: &[&'a str], tags: &[Tag<'a, ANSITagConvertor>], inherited: &Style,
+        ) {
+            self.0.push(format!(
+                "enter {raw:?} {} {}",
+                tags.len(),
+                inherited.render()
+            ));
+        }
+
+        fn exit_element(&mut self, raw: &[&'a str], inherited: &Style) {
+            self.0.push(format!("exit {raw:?} {}", inherited.render()));
+        }
+
+        fn text(&mut self, text: &'a str, style: &Style) {
+            self.0.push(format!("{text} {}", style.render()));
+        }
+    }
+
+    #[test]
+    fn test_walk_events() {
+        let blue = Style::new().bg_color(Some(AnsiColor::Blue.into()));
+        let blue_green = blue.fg_color(Some(AnsiColor::Green.into()));
+
+        let line = parse("<bg:blue a<green,unknown b\\<>>").unwrap().remove(0);
+        let mut events = Events::default();
+        walk(line, &mut ANSITagConvertor::default(), &mut events);
+
+        assert_eq!(events.0, vec![
+            format!("enter [\"bg:blue\"] 1 {}", Style::new().render()),
+            format!("a {}", blue.render()),
+            format!("enter [\"green\", \"unknown\"] 1 {}", blue.render()),
+            format!("b {}", blue_green.render()),
+            format!("< {}", blue_green.render()),
+            format!("exit [\"green\", \"unknown\"] {}", blue.render()),
+            format!("exit [\"bg:blue\"] {}", Style::new().render()),
+        ]);
+    }
+
+    #[test]
+    fn test_walk_converted_has_no_raw_tags() {
+        let line = parse("<b x>").unwrap().remove(0);
+        let line = <ANSITagConvertor>::default().convert_line(line);
+        let mut events = Events::default();
+        walk_converted(line, &mut events);
+
+        let bold = Style::new().bold();
+        assert_eq!(events.0, vec![
+            format!("enter [] 1 {}", Style::new().render()),
+            format!("x {}", bold.render()),
+            format!("exit [] {}", Style::new().render()),
+        ]);
+    }
+}
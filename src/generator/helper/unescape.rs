@@ -26,6 +26,13 @@ pub struct Unescape<'a> {
     cursor: usize,
 }
 
+impl Unescape<'_> {
+    /// Byte offset of the next piece in escaped text.
+    pub(crate) fn offset(&self) -> usize {
+        self.cursor
+    }
+}
+
 impl<'a> Iterator for Unescape<'a> {
     type Item = &'a str;
 
@@ -34,7 +41,12 @@ impl<'a> Iterator for Unescape<'a> {
             return None;
         }
 
-        let start = self.cursor + 1;
+        // First char is never a `\` which starts a escape, skip it as a whole, it may be non-ASCII
+        let first = self.escaped[self.cursor..]
+            .chars()
+            .next()
+            .map_or(1, char::len_utf8);
+        let start = self.cursor + first;
         let end = if start >= self.escaped.len() {
             self.escaped.len()
         } else {
@@ -79,6 +91,13 @@ mod test {
         test_unescape!("a\\\\" => "a", "\\");
     }
 
+    #[test]
+    fn test_non_ascii_before_escape() {
+        test_unescape!("é\\<b" => "é", "<b");
+        test_unescape!("a\\<中" => "a", "<中");
+        test_unescape!("\\é" => "é");
+    }
+
     #[test]
     fn test_escaped_string_multi() {
         test_unescape!("1\\<2\\<3 \\\\ 3\\>2\\>1" => "1", "<2", "<3 ", "\\ 3", ">2", ">1");
@@ -1,10 +1,13 @@
 //! Generator generates final output for showing.
 
-use std::fmt::{Debug, Display};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display};
 
 use crate::{Error, error::LocatedError, parser::ItemG};
 
 pub mod helper;
+mod policy;
+mod stream;
 mod tag;
 
 #[cfg(feature = "ansi")]
@@ -19,11 +22,34 @@ pub use self::ratatui::RatatuiTextGenerator;
 
 #[cfg(feature = "crossterm")]
 pub mod crossterm;
+
+#[cfg(feature = "cursive")]
+pub mod cursive;
+
+#[cfg(feature = "clap")]
+pub mod clap;
+
+#[cfg(feature = "console")]
+pub mod console;
+
+#[cfg(feature = "nu-ansi-term")]
+pub mod nu_ansi_term;
 // TODO: termion generator
+pub use policy::{ColorDepth, Disallowed, DisallowedTag, TagList, TagPolicy};
+pub(crate) use stream::convert_and_generate_line;
+pub use stream::{Buffered, StreamGenerator, Streamed};
 pub use tag::{Tag, TagConvertor, TagG};
 
+#[cfg(feature = "clap")]
+pub use self::clap::ClapStyledStrGenerator;
+#[cfg(feature = "console")]
+pub use self::console::ConsoleStyledObjectsGenerator;
 #[cfg(feature = "crossterm")]
 pub use self::crossterm::CrosstermCommandsGenerator;
+#[cfg(feature = "cursive")]
+pub use self::cursive::CursiveStyledStringGenerator;
+#[cfg(feature = "nu-ansi-term")]
+pub use self::nu_ansi_term::NuAnsiTermGenerator;
 
 /// Generator generates final output to show tui markup in some backend.
 ///
@@ -0,0 +1,182 @@
+//! Generator implementations for nu-ansi-term crate.
+
+mod span;
+mod tag;
+
+use nu_ansi_term::{AnsiString, Style};
+pub use tag::NuAnsiTermTagConvertor;
+
+use crate::{
+    generator::{
+        Generator,
+        helper::{
+            CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize, SourceMap,
+            SourceMapGenerator, flatten_into, flatten_merged_into,
+        },
+    },
+    parser::{ItemC, ItemG},
+};
+
+/// Generator for [nu-ansi-term crate][nu_ansi_term], generated result is a series of it's
+/// [`AnsiString`]s.
+///
+/// See [docs/ratatui-tags.ebnf] for supported tags, nu-ansi-term supports the same color and
+/// modifier set, except `sb` and `rb` are both normal blink.
+///
+/// ## Example
+///
+/// ```
+/// use nu_ansi_term::{AnsiStrings, Color, Style};
+/// use tui_markup::{compile, generator::NuAnsiTermGenerator};
+///
+/// let strings = compile::<NuAnsiTermGenerator>("I have a <green green text>").unwrap();
+///
+/// assert_eq!(strings, vec![
+///     Style::new().paint("I have a "),
+///     Color::Green.paint("green text"),
+/// ]);
+///
+/// println!("{}", AnsiStrings(&strings));
+/// ```
+///
+/// ### With custom tags
+///
+/// ```
+/// use nu_ansi_term::{AnsiStrings, Color, Style};
+/// use tui_markup::{compile_with, generator::NuAnsiTermGenerator};
+///
+/// let g = NuAnsiTermGenerator::new(|tag: &str| match tag {
+///     "keyboard" => Some(Color::Green.on(Color::White).bold()),
+///     _ => None,
+/// });
+///
+/// let strings = compile_with("Press <keyboard W> to move up", g).unwrap();
+///
+/// println!("{}", AnsiStrings(&strings));
+/// ```
+///
+/// ### Show output
+///
+/// Wrap the result in [`AnsiStrings`](nu_ansi_term::AnsiStrings) and print it, or convert it to
+/// [`String`] for APIs like `reedline`'s prompt.
+///
+/// [docs/ratatui-tags.ebnf]: https://github.com/7sDream/tui-markup/blob/master/docs/ratatui-tags.ebnf
+#[derive(Debug)]
+pub struct NuAnsiTermGenerator<P = NoopCustomTagParser<Style>> {
+    convertor: NuAnsiTermTagConvertor<P>,
+    merge_spans: bool,
+    sanitize: Sanitize,
+}
+
+impl<P> Default for NuAnsiTermGenerator<P> {
+    fn default() -> Self {
+        Self {
+            convertor: NuAnsiTermTagConvertor::<P>::default(),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
+        }
+    }
+}
+
+impl<P> NuAnsiTermGenerator<P> {
+    /// Create a new generator, with a custom tag parser.
+    pub fn new(p: P) -> Self {
+        Self {
+            convertor: NuAnsiTermTagConvertor::new(p),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
+        }
+    }
+
+    /// Merge adjacent spans with same style into one, including text split by escapes. Disabled by
+    /// default.
+    ///
+    /// See [`flatten_merged`][super::helper::flatten_merged] for details.
+    #[must_use]
+    pub fn merge_spans(mut self, merge: bool) -> Self {
+        self.merge_spans = merge;
+        self
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.sanitize = policy;
+        self
+    }
+}
+
+impl<P> NuAnsiTermGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn build<'a>(
+        &self, markup: Vec<Vec<ItemC<'a, NuAnsiTermTagConvertor<P>>>>,
+        mut map: Option<&mut SourceMap<'a>>,
+    ) -> Vec<AnsiString<'a>> {
+        let mut spans = Vec::with_capacity(markup.len());
+        for (i, line) in markup.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Style::new().paint("\n"));
+                if let Some(map) = map.as_deref_mut() {
+                    map.push_span([]);
+                }
+            }
+            if self.merge_spans {
+                flatten_merged_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            } else {
+                flatten_into(line, &mut spans, self.sanitize, map.as_deref_mut());
+            }
+        }
+        spans
+    }
+}
+
+impl<'a, P> Generator<'a> for NuAnsiTermGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    type Convertor = NuAnsiTermTagConvertor<P>;
+    type Err = GeneratorInfallible;
+    type Output = Vec<AnsiString<'a>>;
+
+    fn convertor(&mut self) -> &mut Self::Convertor {
+        &mut self.convertor
+    }
+
+    fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, None))
+    }
+}
+
+impl<'a, P> SourceMapGenerator<'a> for NuAnsiTermGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, Some(map)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nu_ansi_term::{Color, Style};
+
+    use crate::{compile, generator::NuAnsiTermGenerator};
+
+    #[test]
+    fn test_nested_element() {
+        assert_eq!(
+            compile::<NuAnsiTermGenerator>("<bg:blue one <green,b two>>\n<gray+ three>"),
+            Ok(vec![
+                Style::new().on(Color::Blue).paint("one "),
+                Color::Green.on(Color::Blue).bold().paint("two"),
+                Style::new().paint("\n"),
+                Color::DarkGray.paint("three"),
+            ])
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+use nu_ansi_term::{AnsiString, Color, Style};
+
+use crate::generator::{
+    Tag, TagConvertor,
+    helper::{FlattenableCowSpan, FlattenableSpan, FlattenableStyle, IntoOwned},
+};
+
+impl<'a, C> From<Tag<'a, C>> for Style
+where
+    C: TagConvertor<'a, Color = Color, Modifier = Style, Custom = Style>,
+{
+    fn from(t: Tag<'a, C>) -> Self {
+        match t {
+            Tag::Fg(c) => Style::new().fg(c),
+            Tag::Bg(c) => Style::new().on(c),
+            Tag::Modifier(s) | Tag::Custom(s) => s,
+        }
+    }
+}
+
+impl FlattenableStyle for Style {
+    /// `other` fg/bg override `self`; modifiers are additive.
+    fn patch(self, other: Self) -> Self {
+        Self {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            is_bold: self.is_bold || other.is_bold,
+            is_dimmed: self.is_dimmed || other.is_dimmed,
+            is_italic: self.is_italic || other.is_italic,
+            is_underline: self.is_underline || other.is_underline,
+            is_blink: self.is_blink || other.is_blink,
+            is_reverse: self.is_reverse || other.is_reverse,
+            is_hidden: self.is_hidden || other.is_hidden,
+            is_strikethrough: self.is_strikethrough || other.is_strikethrough,
+            prefix_with_reset: self.prefix_with_reset || other.prefix_with_reset,
+        }
+    }
+}
+
+impl<'a> FlattenableSpan<'a, Style> for AnsiString<'a> {
+    fn with_style(s: &'a str, style: Option<Style>) -> Self {
+        style.unwrap_or_default().paint(s)
+    }
+}
+
+impl<'a> FlattenableCowSpan<'a, Style> for AnsiString<'a> {
+    fn with_style_cow(s: Cow<'a, str>, style: Option<Style>) -> Self {
+        style.unwrap_or_default().paint(s)
+    }
+}
+
+impl IntoOwned for Vec<AnsiString<'_>> {
+    type Owned = Vec<AnsiString<'static>>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.iter()
+            .map(|s| s.style_ref().paint(s.as_str().to_owned()))
+            .collect()
+    }
+}
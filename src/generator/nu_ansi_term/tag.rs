@@ -0,0 +1,83 @@
+use nu_ansi_term::{Color, Style};
+
+use crate::{
+    generator::{
+        TagConvertor,
+        helper::{CustomTagParser, NoopCustomTagParser},
+    },
+    parser::hex_rgb,
+};
+
+/// Tag convertor for [`NuAnsiTermGenerator`](super::NuAnsiTermGenerator).
+#[derive(Debug)]
+pub struct NuAnsiTermTagConvertor<P = NoopCustomTagParser<Style>> {
+    custom_tag_parser: Option<P>,
+}
+
+impl<P> Default for NuAnsiTermTagConvertor<P> {
+    fn default() -> Self {
+        Self {
+            custom_tag_parser: None,
+        }
+    }
+}
+
+impl<P> NuAnsiTermTagConvertor<P> {
+    /// Create a new tag convertor with custom tag parser.
+    pub fn new(p: P) -> Self {
+        Self {
+            custom_tag_parser: Some(p),
+        }
+    }
+}
+
+impl<'a, P> TagConvertor<'a> for NuAnsiTermTagConvertor<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    type Color = Color;
+    type Custom = Style;
+    type Modifier = Style;
+
+    fn parse_color(&mut self, s: &str) -> Option<Self::Color> {
+        Some(match s {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" | "purple" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "gray" => Color::White,
+            "gray+" => Color::DarkGray,
+            "red-" => Color::LightRed,
+            "green-" => Color::LightGreen,
+            "yellow-" => Color::LightYellow,
+            "blue-" => Color::LightBlue,
+            "magenta-" | "purple-" => Color::LightMagenta,
+            "cyan-" => Color::LightCyan,
+            "white" => Color::LightGray,
+            s => hex_rgb(s)
+                .map(|(r, g, b)| Color::Rgb(r, g, b))
+                .or_else(|| s.parse::<u8>().ok().map(Color::Fixed))?,
+        })
+    }
+
+    fn parse_modifier(&mut self, s: &str) -> Option<Self::Modifier> {
+        Some(match s {
+            "b" => Style::new().bold(),
+            "d" => Style::new().dimmed(),
+            "i" => Style::new().italic(),
+            "u" => Style::new().underline(),
+            "r" => Style::new().reverse(),
+            "sb" | "rb" => Style::new().blink(),
+            "h" => Style::new().hidden(),
+            "s" => Style::new().strikethrough(),
+            _ => return None,
+        })
+    }
+
+    fn parse_custom_tag(&mut self, s: &str) -> Option<Self::Custom> {
+        self.custom_tag_parser.as_mut().and_then(|f| f.parse(s))
+    }
+}
@@ -0,0 +1,484 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use crate::{
+    error::LocatedError,
+    generator::{Tag, TagConvertor},
+    parser::{Item, VisitMut, hex_rgb, line_at, line_starts, visit_mut},
+};
+
+/// Allowlist or denylist of tag names, used by [`TagPolicy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TagList {
+    /// Allow all tags.
+    #[default]
+    All,
+    /// Only allow tags in the list.
+    Allow(Vec<String>),
+    /// Allow all tags except tags in the list.
+    Deny(Vec<String>),
+}
+
+impl TagList {
+    /// Create a allowlist.
+    pub fn allow<I>(names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self::Allow(names.into_iter().map(Into::into).collect())
+    }
+
+    /// Create a denylist.
+    pub fn deny<I>(names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self::Deny(names.into_iter().map(Into::into).collect())
+    }
+
+    /// Check if a tag name is allowed.
+    #[must_use]
+    pub fn allows(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Allow(names) => names.iter().any(|n| n == name),
+            Self::Deny(names) => names.iter().all(|n| n != name),
+        }
+    }
+}
+
+/// Color depth of color tags, in the color syntax of builtin generators.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// Named colors, like `red` or `gray+`.
+    Named,
+    /// Named colors and xterm-256 color index, like `208`.
+    Indexed,
+    /// All colors, includes 24 bit rgb color like `66ccff`.
+    #[default]
+    Rgb,
+}
+
+impl ColorDepth {
+    fn of(color: &str) -> Self {
+        if hex_rgb(color).is_some() {
+            Self::Rgb
+        } else if color.parse::<u8>().is_ok() {
+            Self::Indexed
+        } else {
+            Self::Named
+        }
+    }
+}
+
+/// What [`TagPolicy::apply`] does with disallowed tags.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Disallowed {
+    /// Remove them from their elements, children of the elements are kept.
+    #[default]
+    Drop,
+    /// Return a [`DisallowedTag`] error of the first one.
+    Reject,
+}
+
+/// Error of [`TagPolicy::apply`], the first disallowed tag in source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisallowedTag<'a> {
+    tag: &'a str,
+    // Line number in source
+    line: usize,
+    // Byte offset of the tag in the line
+    offset: usize,
+}
+
+impl<'a> DisallowedTag<'a> {
+    /// The disallowed tag.
+    #[must_use]
+    pub fn tag(&self) -> &'a str {
+        self.tag
+    }
+}
+
+impl Display for DisallowedTag<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (line, offset) = self.location();
+        write!(f, "tag '{}' not allowed near {}:{}", self.tag, line, offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DisallowedTag<'_> {}
+
+impl LocatedError for DisallowedTag<'_> {
+    fn location(&self) -> (usize, usize) {
+        (self.line + 1, self.offset + 1)
+    }
+}
+
+/// Byte offset of a tag in a line of source, by index of its element in the line and its index
+/// in the tag list.
+fn tag_offset(line: &str, element: usize, tag: usize) -> Option<usize> {
+    let mut count = 0;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '<' if count == element => {
+                let tags = line[i + 1..].split([',', ' ']).take(tag);
+                return Some(tags.fold(i + 1, |offset, tag| offset + tag.len() + 1));
+            }
+            '<' => count += 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A policy of allowed tags, wraps a [`TagConvertor`].
+///
+/// Raw tags are converted by the wrapped convertor to know their type, then checked by lists of
+/// the type: colors and modifiers are checked by their value, like `red` in `bg:red` and `b` in
+/// `mod:b`, custom tags are checked by the whole raw tag. Colors are also limited by
+/// [`ColorDepth`]. Tags which the convertor can't convert are always disallowed, so the wrapped
+/// convertor should understand the same custom tags as the one in generator.
+///
+/// Use [`apply`][Self::apply] to check the AST between [`parse`][crate::parser::parse] and
+/// [`convert_ast`][TagConvertor::convert_ast] of a generator. It also implements
+/// [`TagConvertor`] itself, which drops disallowed tags, for generators with a generic convertor.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::{
+///     LocatedError,
+///     generator::{ColorDepth, Disallowed, TagConvertor, TagList, TagPolicy},
+///     parser::parse,
+/// };
+/// # struct Convertor;
+/// # impl TagConvertor<'_> for Convertor {
+/// #     type Color = ();
+/// #     type Modifier = ();
+/// #     type Custom = ();
+/// #     fn parse_color(&mut self, s: &str) -> Option<()> {
+/// #         matches!(s, "red" | "208" | "66ccff").then_some(())
+/// #     }
+/// #     fn parse_modifier(&mut self, s: &str) -> Option<()> {
+/// #         matches!(s, "b" | "h" | "sb" | "rb").then_some(())
+/// #     }
+/// #     fn parse_custom_tag(&mut self, s: &str) -> Option<()> {
+/// #         (s == "admin").then_some(())
+/// #     }
+/// # }
+///
+/// // A convertor of the generator, like `ANSITagConvertor::default()`
+/// let mut policy = TagPolicy::new(Convertor)
+///     .modifiers(TagList::deny(["h", "sb", "rb"]))
+///     .custom_tags(TagList::Allow(vec![]))
+///     .color_depth(ColorDepth::Indexed);
+///
+/// let source = "<b,h hi> <admin,208 all> <66ccff,red x>";
+/// let ast = policy.apply(source, parse(source).unwrap()).unwrap();
+/// assert_eq!(ast, parse("<b hi> <208 all> <red x>").unwrap());
+///
+/// let mut policy = policy.on_disallowed(Disallowed::Reject);
+/// let error = policy.apply(source, parse(source).unwrap()).unwrap_err();
+/// assert_eq!(error.tag(), "h");
+/// assert_eq!(error.location(), (1, 4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TagPolicy<C> {
+    convertor: C,
+    colors: TagList,
+    modifiers: TagList,
+    custom_tags: TagList,
+    color_depth: ColorDepth,
+    disallowed: Disallowed,
+}
+
+impl<C> TagPolicy<C> {
+    /// Create a policy which allows all tags, wraps a convertor.
+    pub fn new(convertor: C) -> Self {
+        Self {
+            convertor,
+            colors: TagList::default(),
+            modifiers: TagList::default(),
+            custom_tags: TagList::default(),
+            color_depth: ColorDepth::default(),
+            disallowed: Disallowed::default(),
+        }
+    }
+
+    /// Set allowed colors of foreground and background, by color name like `red` or `66ccff`.
+    #[must_use]
+    pub fn colors(mut self, colors: TagList) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Set allowed modifiers, by modifier name like `b` or `sb`.
+    #[must_use]
+    pub fn modifiers(mut self, modifiers: TagList) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Set allowed custom tags.
+    #[must_use]
+    pub fn custom_tags(mut self, custom_tags: TagList) -> Self {
+        self.custom_tags = custom_tags;
+        self
+    }
+
+    /// Set max color depth, [`ColorDepth::Rgb`] by default.
+    #[must_use]
+    pub fn color_depth(mut self, depth: ColorDepth) -> Self {
+        self.color_depth = depth;
+        self
+    }
+
+    /// Set what [`apply`][Self::apply] does with disallowed tags, [`Disallowed::Drop`] by default.
+    #[must_use]
+    pub fn on_disallowed(mut self, disallowed: Disallowed) -> Self {
+        self.disallowed = disallowed;
+        self
+    }
+
+    /// Get the wrapped convertor.
+    pub fn into_inner(self) -> C {
+        self.convertor
+    }
+
+    fn allows<'a>(&self, s: &str, tag: &Tag<'a, C>) -> bool
+    where
+        C: TagConvertor<'a>,
+    {
+        let value = s.split_once(':').map_or(s, |(_, value)| value);
+        match tag {
+            Tag::Fg(_) | Tag::Bg(_) => {
+                self.colors.allows(value) && ColorDepth::of(value) <= self.color_depth
+            }
+            Tag::Modifier(_) => self.modifiers.allows(value),
+            Tag::Custom(_) => self.custom_tags.allows(s),
+        }
+    }
+
+    /// Check and remove disallowed tags of raw AST, or reject it by the first disallowed tag.
+    ///
+    /// `ast` should be parsed from `source`, which is used for location of error.
+    ///
+    /// ## Errors
+    ///
+    /// If there is any disallowed tag and the policy is [`Disallowed::Reject`].
+    pub fn apply<'a>(
+        &mut self, source: &'a str, mut ast: Vec<Vec<Item<'a>>>,
+    ) -> Result<Vec<Vec<Item<'a>>>, DisallowedTag<'a>>
+    where
+        C: TagConvertor<'a>,
+    {
+        struct Checker<'p, 'a, C> {
+            policy: &'p mut TagPolicy<C>,
+            // Count of elements visited in current line
+            elements: usize,
+            // The tag, index of its element in line, and its index in the tag list
+            rejected: Option<(&'a str, usize, usize)>,
+        }
+
+        impl<'a, C: TagConvertor<'a>> VisitMut<'a, &'a str> for Checker<'_, 'a, C> {
+            fn enter_element(&mut self, tags: &mut Vec<&'a str>, _stack: &[&[&'a str]]) {
+                if self.rejected.is_some() {
+                    return;
+                }
+                match self.policy.disallowed {
+                    Disallowed::Drop => tags.retain(|tag| self.policy.convert_tag(tag).is_some()),
+                    Disallowed::Reject => {
+                        self.rejected = tags
+                            .iter()
+                            .position(|tag| self.policy.convert_tag(tag).is_none())
+                            .map(|i| (tags[i], self.elements, i));
+                    }
+                }
+                self.elements += 1;
+            }
+        }
+
+        let mut checker = Checker {
+            policy: self,
+            elements: 0,
+            rejected: None,
+        };
+        let starts = line_starts(source);
+
+        for (index, line) in ast.iter_mut().enumerate() {
+            checker.elements = 0;
+            visit_mut(line, &mut checker);
+
+            if let Some((tag, element, position)) = checker.rejected {
+                let line = line_at(source, &starts, index).unwrap_or_default();
+                return Err(DisallowedTag {
+                    tag,
+                    line: index,
+                    offset: tag_offset(line, element, position).unwrap_or_default(),
+                });
+            }
+        }
+
+        Ok(ast)
+    }
+}
+
+impl<'a, C: TagConvertor<'a>> TagConvertor<'a> for TagPolicy<C> {
+    type Color = C::Color;
+    type Custom = C::Custom;
+    type Modifier = C::Modifier;
+
+    fn parse_color(&mut self, s: &str) -> Option<Self::Color> {
+        self.convertor.parse_color(s)
+    }
+
+    fn parse_modifier(&mut self, s: &str) -> Option<Self::Modifier> {
+        self.convertor.parse_modifier(s)
+    }
+
+    fn parse_custom_tag(&mut self, s: &str) -> Option<Self::Custom> {
+        self.convertor.parse_custom_tag(s)
+    }
+
+    fn convert_tag(&mut self, s: &'a str) -> Option<Tag<'a, Self>> {
+        let tag = self.convertor.convert_tag(s)?;
+        if !self.allows(s, &tag) {
+            return None;
+        }
+
+        Some(match tag {
+            Tag::Fg(color) => Tag::Fg(color),
+            Tag::Bg(color) => Tag::Bg(color),
+            Tag::Modifier(modifier) => Tag::Modifier(modifier),
+            Tag::Custom(custom) => Tag::Custom(custom),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec};
+
+    use super::{ColorDepth, Disallowed, TagList, TagPolicy};
+    use crate::{
+        error::LocatedError,
+        generator::{Tag, TagConvertor},
+        parser::{Item, parse},
+        printer::print,
+        test_util::{Color, Convertor},
+    };
+
+    /// Source after applying the policy, elements without tags are printed as their children.
+    fn applied(mut policy: TagPolicy<Convertor>, source: &str) -> String {
+        print(&policy.apply(source, parse(source).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_allow_all_by_default() {
+        let source = "<b,h,sb x> <bg:66ccff,208,red y> <keyboard,internal z>";
+        assert_eq!(applied(TagPolicy::new(Convertor), source), source);
+    }
+
+    #[test]
+    fn test_drop() {
+        let policy = TagPolicy::new(Convertor)
+            .modifiers(TagList::deny(["h", "sb", "rb", "r"]))
+            .custom_tags(TagList::allow(["keyboard"]));
+        assert_eq!(
+            applied(
+                policy,
+                "<b,mod:h,sb x <internal,keyboard,r y>>\n<rb,unknown z>"
+            ),
+            "<b x <keyboard y>>\nz"
+        );
+    }
+
+    #[test]
+    fn test_colors() {
+        let source = "<red a><fg:gray+ b><bg:208 c><66ccff d>";
+
+        let policy = TagPolicy::new(Convertor).color_depth(ColorDepth::Named);
+        assert_eq!(applied(policy, source), "<red a><fg:gray+ b>cd");
+
+        let policy = TagPolicy::new(Convertor).color_depth(ColorDepth::Indexed);
+        assert_eq!(applied(policy, source), "<red a><fg:gray+ b><bg:208 c>d");
+
+        let policy = TagPolicy::new(Convertor).colors(TagList::deny(["gray+", "66ccff"]));
+        assert_eq!(applied(policy, source), "<red a>b<bg:208 c>d");
+    }
+
+    #[test]
+    fn test_custom_tag_is_not_checked_as_color() {
+        // `red` is a custom tag of the convertor, so it's checked by the custom tag list
+        let policy = TagPolicy::new(Convertor)
+            .colors(TagList::Allow(vec![]))
+            .custom_tags(TagList::allow(["red"]));
+        assert_eq!(applied(policy, "<red a>"), "<red a>");
+
+        let policy = TagPolicy::new(Convertor).custom_tags(TagList::deny(["red"]));
+        assert_eq!(applied(policy, "<red a>"), "a");
+    }
+
+    #[test]
+    fn test_reject() {
+        let mut policy = TagPolicy::new(Convertor)
+            .modifiers(TagList::deny(["h"]))
+            .on_disallowed(Disallowed::Reject);
+
+        let source = "<b ok>\r\n<b x <r,unknown,h y>>";
+        let error = policy.apply(source, parse(source).unwrap()).unwrap_err();
+        assert_eq!(error.tag(), "unknown");
+        assert_eq!(error.location(), (2, 9));
+        assert_eq!(error.to_string(), "tag 'unknown' not allowed near 2:9");
+
+        let source = "<b ok>\n<mod:h y>";
+        let error = policy.apply(source, parse(source).unwrap()).unwrap_err();
+        assert_eq!(error.location(), (2, 2));
+
+        let source = "\\<<b a\\<b> <b <b,r b> <b,r,mod:h c>>";
+        let error = policy.apply(source, parse(source).unwrap()).unwrap_err();
+        assert_eq!(error.location(), (1, 28));
+
+        // Not parsed from the source, but still an error
+        let error = policy.apply("", parse("<h x>").unwrap()).unwrap_err();
+        assert_eq!(error.location(), (1, 1));
+
+        let source = "<b,r ok>";
+        assert!(policy.apply(source, parse(source).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_convertor() {
+        let mut policy = TagPolicy::new(Convertor)
+            .modifiers(TagList::allow(["b"]))
+            .color_depth(ColorDepth::Named);
+
+        assert!(matches!(policy.convert_tag("b"), Some(Tag::Modifier(()))));
+        assert!(matches!(
+            policy.convert_tag("bg:red"),
+            Some(Tag::Bg(Color::Named))
+        ));
+        assert!(policy.convert_tag("h").is_none());
+        assert!(policy.convert_tag("208").is_none());
+        assert!(policy.convert_tag("x").is_none());
+
+        let line = policy.convert_line(parse("<b,h,208 x>").unwrap().remove(0));
+        assert!(matches!(&line[0], Item::Element(tags, _) if tags.len() == 1));
+    }
+
+    #[test]
+    fn test_list() {
+        assert!(TagList::All.allows("x"));
+        assert!(TagList::allow(["x"]).allows("x"));
+        assert!(!TagList::allow(["x"]).allows("y"));
+        assert!(!TagList::deny(vec!["x"]).allows("x"));
+        assert!(TagList::deny(vec!["x"]).allows("y"));
+    }
+}
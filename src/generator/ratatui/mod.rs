@@ -14,9 +14,12 @@ pub use tag::RatatuiTagConvertor;
 use crate::{
     generator::{
         Generator,
-        helper::{CustomTagParser, GeneratorInfallible, NoopCustomTagParser, flatten},
+        helper::{
+            CustomTagParser, GeneratorInfallible, NoopCustomTagParser, Sanitize, SourceMap,
+            SourceMapGenerator, flatten_into, flatten_merged_into,
+        },
     },
-    parser::ItemG,
+    parser::{ItemC, ItemG},
 };
 
 /// Generator for `ratatui` crate's [Text] type.
@@ -146,12 +149,16 @@ use crate::{
 #[derive(Debug)]
 pub struct RatatuiTextGenerator<P = NoopCustomTagParser<Style>> {
     convertor: RatatuiTagConvertor<P>,
+    merge_spans: bool,
+    sanitize: Sanitize,
 }
 
 impl<P> Default for RatatuiTextGenerator<P> {
     fn default() -> Self {
         Self {
             convertor: RatatuiTagConvertor::<P>::default(),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
         }
     }
 }
@@ -161,8 +168,57 @@ impl<P> RatatuiTextGenerator<P> {
     pub fn new(p: P) -> Self {
         RatatuiTextGenerator {
             convertor: RatatuiTagConvertor::new(p),
+            merge_spans: false,
+            sanitize: Sanitize::default(),
         }
     }
+
+    /// Merge adjacent spans with same style into one, including text split by escapes. Disabled by
+    /// default.
+    ///
+    /// See [`flatten_merged`][super::helper::flatten_merged] for details.
+    #[must_use]
+    pub fn merge_spans(mut self, merge: bool) -> Self {
+        self.merge_spans = merge;
+        self
+    }
+
+    /// Policy for control characters and bidi overrides in plain text, [`Sanitize::Replace`] by
+    /// default. Use [`Sanitize::Keep`] only for trusted source.
+    #[must_use]
+    pub fn sanitize(mut self, policy: Sanitize) -> Self {
+        self.sanitize = policy;
+        self
+    }
+}
+
+impl<P> RatatuiTextGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn build<'a>(
+        &self, markup: Vec<Vec<ItemC<'a, RatatuiTagConvertor<P>>>>,
+        mut map: Option<&mut SourceMap<'a>>,
+    ) -> Text<'a> {
+        // Spans of each line are flattened into a reused buffer, then moved into a vector of exact
+        // size, which is cheaper than growing a new vector for every line.
+        let mut buffer = vec![];
+        Text::from(
+            markup
+                .into_iter()
+                .map(|line| {
+                    if self.merge_spans {
+                        flatten_merged_into(line, &mut buffer, self.sanitize, map.as_deref_mut());
+                    } else {
+                        flatten_into(line, &mut buffer, self.sanitize, map.as_deref_mut());
+                    }
+                    #[allow(clippy::drain_collect)] // take would give up the reused buffer
+                    let spans: Vec<_> = buffer.drain(..).collect();
+                    Line::from(spans)
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
 }
 
 impl<'a, P> Generator<'a> for RatatuiTextGenerator<P>
@@ -178,11 +234,17 @@ where
     }
 
     fn generate(&mut self, markup: Vec<Vec<ItemG<'a, Self>>>) -> Result<Self::Output, Self::Err> {
-        Ok(Text::from(
-            markup
-                .into_iter()
-                .map(|line| Line::from(flatten(line)))
-                .collect::<Vec<_>>(),
-        ))
+        Ok(self.build(markup, None))
+    }
+}
+
+impl<'a, P> SourceMapGenerator<'a> for RatatuiTextGenerator<P>
+where
+    P: CustomTagParser<Output = Style>,
+{
+    fn generate_with_source_map(
+        &mut self, markup: Vec<Vec<ItemG<'a, Self>>>, map: &mut SourceMap<'a>,
+    ) -> Result<Self::Output, Self::Err> {
+        Ok(self.build(markup, Some(map)))
     }
 }
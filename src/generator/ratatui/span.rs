@@ -1,11 +1,13 @@
+use std::borrow::Cow;
+
 use ratatui_core::{
     style::{Color, Modifier, Style},
-    text::Span,
+    text::{Line, Span, Text},
 };
 
 use crate::generator::{
     Tag, TagConvertor,
-    helper::{FlattenableSpan, FlattenableStyle},
+    helper::{FlattenableCowSpan, FlattenableSpan, FlattenableStyle, IntoOwned},
 };
 
 impl<'a, C> From<Tag<'a, C>> for Style
@@ -36,3 +38,34 @@ impl<'a> FlattenableSpan<'a, Style> for Span<'a> {
         }
     }
 }
+
+impl<'a> FlattenableCowSpan<'a, Style> for Span<'a> {
+    fn with_style_cow(s: Cow<'a, str>, style: Option<Style>) -> Self {
+        match style {
+            Some(style) => Span::styled(s, style),
+            None => Span::raw(s),
+        }
+    }
+}
+
+/// Text of every span is copied, styles and alignments of lines are kept.
+impl IntoOwned for Text<'_> {
+    type Owned = Text<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        let lines = self.lines.into_iter().map(|line| Line {
+            spans: line
+                .spans
+                .into_iter()
+                .map(|span| Span::styled(span.content.into_owned(), span.style))
+                .collect(),
+            style: line.style,
+            alignment: line.alignment,
+        });
+        Text {
+            lines: lines.collect(),
+            style: self.style,
+            alignment: self.alignment,
+        }
+    }
+}
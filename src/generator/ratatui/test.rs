@@ -84,3 +84,85 @@ fn test_custom_tag_element() {
 fn test_invalid_element() {
     test_ok!(elem!("qwerty" ; pt!("one")) => Span::raw("one"));
 }
+
+#[test]
+fn test_source_map() {
+    let source = "<b one>\n\\<two";
+    let (text, map) =
+        crate::compile_with_source_map(source, <crate::generator::RatatuiTextGenerator>::default())
+            .unwrap();
+
+    assert_eq!(text.lines.len(), 2);
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+        Some(3..6),
+        Some(9..13)
+    ]);
+}
+
+#[test]
+fn test_source_map_of_merged_and_sanitized_spans() {
+    use crate::{compile_with_source_map, generator::RatatuiTextGenerator};
+
+    let source = "a\\<b\x1b<b c>";
+
+    let (text, map) = compile_with_source_map(source, <RatatuiTextGenerator>::default()).unwrap();
+    assert_eq!(text.lines[0].spans[2], Span::raw("^["));
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![
+        Some(0..1),
+        Some(2..4),
+        Some(4..5),
+        Some(8..9)
+    ]);
+    assert_eq!(map.byte_offset(2, 1), Some(4));
+
+    let generator = <RatatuiTextGenerator>::default().merge_spans(true);
+    let (text, map) = compile_with_source_map(source, generator).unwrap();
+    assert_eq!(text.lines[0].spans[0], Span::raw("a<b^["));
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![Some(0..5), Some(8..9)]);
+    assert_eq!(map.segments(0).len(), 3);
+    assert_eq!(map.byte_offset(0, 1), Some(2));
+    assert_eq!(map.byte_offset(0, 4), Some(4));
+    assert_eq!(map.char_offset(0, 2), Some(3));
+    assert_eq!(map.char_offset(0, 3), Some(4));
+}
+
+#[test]
+fn test_merge_spans() {
+    let generator = <crate::generator::RatatuiTextGenerator>::default().merge_spans(true);
+    let text = crate::compile_with("a\\<b<b x><b y>", generator).unwrap();
+
+    assert_eq!(text.lines[0].spans, vec![
+        Span::raw("a<b"),
+        Span::styled("xy", Style::default().add_modifier(Modifier::BOLD)),
+    ]);
+}
+
+#[test]
+fn test_sanitize() {
+    use crate::generator::{RatatuiTextGenerator, helper::Sanitize};
+
+    let source = "a\x1b[2J<b b\u{202e}>";
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+
+    let text = crate::compile::<RatatuiTextGenerator>(source).unwrap();
+    assert_eq!(text.lines[0].spans, vec![
+        Span::raw("a"),
+        Span::raw("^["),
+        Span::raw("[2J"),
+        Span::styled("b", bold),
+        Span::styled("\\u{202e}", bold),
+    ]);
+
+    let generator = <RatatuiTextGenerator>::default()
+        .merge_spans(true)
+        .sanitize(Sanitize::Strip);
+    let text = crate::compile_with(source, generator).unwrap();
+    assert_eq!(text.lines[0].spans, vec![
+        Span::raw("a[2J"),
+        Span::styled("b", bold)
+    ]);
+
+    let generator = <RatatuiTextGenerator>::default().sanitize(Sanitize::Keep);
+    let text = crate::compile_with(source, generator).unwrap();
+    assert_eq!(text.lines[0].spans[0], Span::raw("a\x1b[2J"));
+}
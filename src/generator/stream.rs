@@ -0,0 +1,336 @@
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display};
+
+use crate::{
+    Error,
+    error::LocatedError,
+    generator::{
+        Generator, Tag, TagConvertor,
+        helper::{FlattenableStyle, Piece, Sanitize, flatten_with},
+    },
+    parser::{Item, ItemC, ItemG},
+};
+
+/// Push based generator, which receives styled text piece by piece.
+///
+/// Unlike [Generator], which receives the whole AST after all source is parsed, a stream generator
+/// is driven by [`compile_stream`][crate::compile_stream] or
+/// [`compile_stream_with`][crate::compile_stream_with] directly from the parser, line by line. So
+/// it can start producing output before the whole source is parsed.
+///
+/// Tags are converted by the [`Convertor`][Self::Convertor], and flattened into the
+/// [`Style`][Self::Style] type, in the same way as [`flatten`][super::helper::flatten] function.
+///
+/// Use [Streamed] adapter to use it as a normal [Generator], and [Buffered] adapter for the other
+/// way.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ansi")] {
+/// use anstyle::Style;
+/// use tui_markup::{
+///     compile_stream,
+///     generator::{StreamGenerator, ansi::ANSITagConvertor, helper::GeneratorInfallible},
+/// };
+///
+/// /// Count bold chars in each line.
+/// #[derive(Default)]
+/// struct BoldCounter {
+///     convertor: ANSITagConvertor,
+///     counts: Vec<usize>,
+/// }
+///
+/// impl<'a> StreamGenerator<'a> for BoldCounter {
+///     type Convertor = ANSITagConvertor;
+///     type Err = GeneratorInfallible;
+///     type Output = Vec<usize>;
+///     type Style = Style;
+///
+///     fn convertor(&mut self) -> &mut Self::Convertor {
+///         &mut self.convertor
+///     }
+///
+///     fn begin_line(&mut self) -> Result<(), Self::Err> {
+///         self.counts.push(0);
+///         Ok(())
+///     }
+///
+///     fn text(&mut self, text: &'a str, style: &Style) -> Result<(), Self::Err> {
+///         if style.get_effects().contains(anstyle::Effects::BOLD) {
+///             *self.counts.last_mut().unwrap() += text.chars().count();
+///         }
+///         Ok(())
+///     }
+///
+///     fn finish(&mut self) -> Result<Self::Output, Self::Err> {
+///         Ok(std::mem::take(&mut self.counts))
+///     }
+/// }
+///
+/// assert_eq!(
+///     compile_stream::<BoldCounter>("<b one> two\n<green,b three>"),
+///     Ok(vec![3, 5])
+/// );
+/// # }
+/// ```
+pub trait StreamGenerator<'a> {
+    /// Tag convertor type.
+    type Convertor: TagConvertor<'a>;
+
+    /// Style type which tags are flattened into.
+    type Style: FlattenableStyle + From<Tag<'a, Self::Convertor>>;
+
+    /// Output type.
+    type Output;
+
+    /// Error type, same requirements as [`Generator::Err`].
+    type Err: LocatedError + Display + Debug + Into<Error<'a, Self::Err>>;
+
+    /// Get the tag convertor.
+    fn convertor(&mut self) -> &mut Self::Convertor;
+
+    /// Called before each line of source.
+    ///
+    /// ## Errors
+    ///
+    /// When the generator can't process the input. This should be documented details.
+    fn begin_line(&mut self) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    /// Called for each piece of unescaped plain text, with it's flattened style.
+    ///
+    /// Text not in any element has the [`Default`] style.
+    ///
+    /// ## Errors
+    ///
+    /// When the generator can't process the input. This should be documented details.
+    fn text(&mut self, text: &'a str, style: &Self::Style) -> Result<(), Self::Err>;
+
+    /// Policy for control characters and bidi overrides in text given to [`text`][Self::text],
+    /// [`Sanitize::Replace`] by default.
+    fn sanitize(&self) -> Sanitize {
+        Sanitize::default()
+    }
+
+    /// Called after each line of source.
+    ///
+    /// ## Errors
+    ///
+    /// When the generator can't process the input. This should be documented details.
+    fn end_line(&mut self) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    /// Called for each line of source, with tags converted.
+    ///
+    /// By default the line is flattened, and sent by [`begin_line`][Self::begin_line],
+    /// [`text`][Self::text] and [`end_line`][Self::end_line]. Override it to process the whole
+    /// line at once, then those methods are not called.
+    ///
+    /// ## Errors
+    ///
+    /// When the generator can't process the input. This should be documented details.
+    fn line(&mut self, line: Vec<ItemC<'a, Self::Convertor>>) -> Result<(), Self::Err> {
+        self.begin_line()?;
+        let policy = self.sanitize();
+        for Piece(text, style) in flatten_with::<_, Piece<'a, Self::Style>, _>(line, policy) {
+            self.text(text, &style.unwrap_or_default())?;
+        }
+        self.end_line()
+    }
+
+    /// Called after all lines, to get the final output.
+    ///
+    /// ## Errors
+    ///
+    /// When the generator can't produce output. This should be documented details.
+    fn finish(&mut self) -> Result<Self::Output, Self::Err>;
+}
+
+/// Convert tags of a raw line, then send it into a stream generator.
+pub(crate) fn convert_and_generate_line<'a, G>(
+    generator: &mut G, line: Vec<Item<'a>>,
+) -> Result<(), G::Err>
+where
+    G: StreamGenerator<'a> + ?Sized,
+{
+    let line = generator.convertor().convert_line(line);
+    generator.line(line)
+}
+
+/// Adapter to use a [`StreamGenerator`] as a normal [Generator].
+///
+/// So it can be used in [`compile_with`][crate::compile_with] and other APIs which need a
+/// [Generator]. The whole AST is still built before generating in this case.
+#[derive(Debug, Default)]
+pub struct Streamed<G>(pub G);
+
+impl<'a, G: StreamGenerator<'a>> Generator<'a> for Streamed<G> {
+    type Convertor = G::Convertor;
+    type Err = G::Err;
+    type Output = G::Output;
+
+    fn convertor(&mut self) -> &mut Self::Convertor {
+        self.0.convertor()
+    }
+
+    fn generate(
+        &mut self, markup: Vec<Vec<ItemC<'a, G::Convertor>>>,
+    ) -> Result<Self::Output, Self::Err> {
+        for line in markup {
+            self.0.line(line)?;
+        }
+        self.0.finish()
+    }
+}
+
+/// Adapter to use a normal [Generator] as a [`StreamGenerator`].
+///
+/// Lines are converted as soon as they are parsed, and generated together by the inner generator
+/// in [`finish`][StreamGenerator::finish]. [`compile_with`][crate::compile_with] drives all
+/// generators by this adapter, so builtin generators can be used in
+/// [`compile_stream_with`][crate::compile_stream_with] and other APIs which need a
+/// [`StreamGenerator`] in the same way.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ansi")] {
+/// use tui_markup::{
+///     compile_stream_with,
+///     generator::{ANSIStringsGenerator, Buffered},
+/// };
+///
+/// let generator = Buffered::new(<ANSIStringsGenerator>::default());
+/// let text = compile_stream_with("I have a <green green text>", generator).unwrap();
+///
+/// assert_eq!(text.spans().len(), 2);
+/// # }
+/// ```
+pub struct Buffered<'a, G: Generator<'a>> {
+    generator: G,
+    lines: Vec<Vec<ItemG<'a, G>>>,
+}
+
+impl<'a, G: Generator<'a>> Buffered<'a, G> {
+    /// Create a new adapter of the generator.
+    pub fn new(generator: G) -> Self {
+        Self {
+            generator,
+            lines: Vec::new(),
+        }
+    }
+}
+
+impl<'a, G: Generator<'a>> StreamGenerator<'a> for Buffered<'a, G> {
+    type Convertor = G::Convertor;
+    type Err = G::Err;
+    type Output = G::Output;
+    type Style = ();
+
+    fn convertor(&mut self) -> &mut Self::Convertor {
+        self.generator.convertor()
+    }
+
+    fn text(&mut self, _: &'a str, (): &()) -> Result<(), Self::Err> {
+        // Never called, lines are buffered as a whole
+        Ok(())
+    }
+
+    fn line(&mut self, line: Vec<ItemC<'a, Self::Convertor>>) -> Result<(), Self::Err> {
+        self.lines.push(line);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<Self::Output, Self::Err> {
+        self.generator.generate(core::mem::take(&mut self.lines))
+    }
+}
+
+#[cfg(all(test, feature = "ratatui"))]
+mod test {
+    use ratatui_core::style::{Color, Style};
+
+    use super::{Buffered, StreamGenerator, Streamed};
+    use crate::{
+        compile_stream, compile_stream_with, compile_with,
+        generator::{
+            Generator, RatatuiTextGenerator, TagConvertor, helper::GeneratorInfallible,
+            ratatui::RatatuiTagConvertor,
+        },
+        parser::parse,
+    };
+
+    #[derive(Default)]
+    struct Events {
+        convertor: RatatuiTagConvertor,
+        events: Vec<String>,
+    }
+
+    impl<'a> StreamGenerator<'a> for Events {
+        type Convertor = RatatuiTagConvertor;
+        type Err = GeneratorInfallible;
+        type Output = Vec<String>;
+        type Style = Style;
+
+        fn convertor(&mut self) -> &mut Self::Convertor {
+            &mut self.convertor
+        }
+
+        fn begin_line(&mut self) -> Result<(), Self::Err> {
+            self.events.push("begin".into());
+            Ok(())
+        }
+
+        fn text(&mut self, text: &'a str, style: &Style) -> Result<(), Self::Err> {
+            self.events.push(format!("{text}:{:?}", style.fg));
+            Ok(())
+        }
+
+        fn end_line(&mut self) -> Result<(), Self::Err> {
+            self.events.push("end".into());
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<Self::Output, Self::Err> {
+            Ok(std::mem::take(&mut self.events))
+        }
+    }
+
+    #[test]
+    fn test_stream_events() {
+        let expected = vec![
+            "begin".to_owned(),
+            "a:None".to_owned(),
+            format!("<:{:?}", Some(Color::Green)),
+            "end".to_owned(),
+            "begin".to_owned(),
+            "end".to_owned(),
+        ];
+
+        let source = "a<green \\<>\n\n";
+        assert_eq!(compile_stream::<Events>(source), Ok(expected.clone()));
+        assert_eq!(
+            compile_with(source, Streamed(Events::default())),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_buffered_builtin_generator() {
+        let source = "<green a\\<> b\n\n<b c>";
+
+        let mut generator = <RatatuiTextGenerator>::default().merge_spans(true);
+        let ast = parse(source).unwrap();
+        let ir = generator.convertor().convert_ast(ast);
+        let expected = generator.generate(ir).unwrap();
+
+        let generator = Buffered::new(<RatatuiTextGenerator>::default().merge_spans(true));
+        assert_eq!(compile_stream_with(source, generator), Ok(expected));
+        assert!(
+            compile_stream_with("a\n<b", Buffered::new(<RatatuiTextGenerator>::default())).is_err()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::{
     generator::Generator,
     parser::{Item, ItemC},
@@ -18,6 +20,12 @@ pub enum Tag<'a, C: TagConvertor<'a> + ?Sized> {
     Custom(C::Custom),
 }
 
+/// Tags are ignored by the unit style, see [`FlattenableStyle`][super::helper::FlattenableStyle]
+/// impl of it.
+impl<'a, C: TagConvertor<'a> + ?Sized> From<Tag<'a, C>> for () {
+    fn from(_: Tag<'a, C>) -> Self {}
+}
+
 /// Tag type for a generator G.
 pub type TagG<'a, G> = Tag<'a, <G as Generator<'a>>::Convertor>;
 
@@ -57,15 +65,9 @@ pub trait TagConvertor<'a> {
 
     /// Parse string to a builtin tag type.
     fn parse_built_in_tag(&mut self, s: &str) -> Option<Tag<'a, Self>> {
-        let mut ty_value = s.split(':');
-        let mut ty = ty_value.next()?;
-        let value = ty_value.next().unwrap_or_else(|| {
-            let value = ty;
-            ty = "";
-            value
-        });
-
-        if ty_value.next().is_some() {
+        let (ty, value) = s.split_once(':').unwrap_or(("", s));
+
+        if value.contains(':') {
             return None;
         }
 
@@ -100,10 +102,9 @@ pub trait TagConvertor<'a> {
         match item {
             Item::PlainText(pt) => Item::PlainText(pt),
             Item::Element(spans, items) => {
-                let tags = spans
-                    .into_iter()
-                    .filter_map(|span| self.convert_tag(span))
-                    .collect();
+                // Usually all tags are valid, so allocate exactly once for them
+                let mut tags = Vec::with_capacity(spans.len());
+                tags.extend(spans.into_iter().filter_map(|span| self.convert_tag(span)));
 
                 let subitems = self.convert_line(items);
 
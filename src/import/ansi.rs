@@ -0,0 +1,320 @@
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::writer::MarkupWriter;
+
+const MODIFIERS: [&str; 9] = ["b", "d", "i", "u", "sb", "rb", "r", "h", "s"];
+
+/// Current graphic rendition state of the terminal.
+#[derive(Debug, Default)]
+struct State {
+    fg: Option<String>,
+    bg: Option<String>,
+    // Indexed by SGR code - 1, same order as `MODIFIERS`.
+    modifiers: [bool; 9],
+}
+
+impl State {
+    fn tags(&self) -> String {
+        let mut tags: Vec<String> = Vec::new();
+        tags.extend(self.fg.clone());
+        tags.extend(self.bg.as_ref().map(|bg| format!("bg:{bg}")));
+        tags.extend(
+            MODIFIERS
+                .iter()
+                .zip(self.modifiers)
+                .filter(|(_, on)| *on)
+                .map(|(m, _)| (*m).to_owned()),
+        );
+        tags.join(",")
+    }
+
+    fn apply(&mut self, params: &str) {
+        let mut groups = params.split(';');
+
+        while let Some(group) = groups.next() {
+            // A parameter may have `:` separated sub-parameters, like `4:3` or `38:2::r:g:b`.
+            let mut subs = group.split(':');
+            let code = match subs.next().unwrap_or_default() {
+                "" => 0,
+                p => match p.parse::<u16>() {
+                    Ok(code) => code,
+                    Err(_) => continue,
+                },
+            };
+            let mut color = || {
+                if group.contains(':') {
+                    sub_extended_color(&subs.clone().collect::<Vec<_>>())
+                } else {
+                    extended_color(&mut groups)
+                }
+            };
+
+            match code {
+                0 => *self = Self::default(),
+                // Underline styles, like curly or dotted, are all imported as `u`.
+                4 => self.modifiers[3] = subs.next().is_none_or(|style| style != "0"),
+                1..=9 => self.modifiers[usize::from(code) - 1] = true,
+                21 => self.modifiers[3] = true,
+                22 => {
+                    self.modifiers[0] = false;
+                    self.modifiers[1] = false;
+                }
+                23 => self.modifiers[2] = false,
+                24 => self.modifiers[3] = false,
+                25 => {
+                    self.modifiers[4] = false;
+                    self.modifiers[5] = false;
+                }
+                27 => self.modifiers[6] = false,
+                28 => self.modifiers[7] = false,
+                29 => self.modifiers[8] = false,
+                30..=37 => self.fg = Some(base_color(code - 30)),
+                40..=47 => self.bg = Some(base_color(code - 40)),
+                90..=97 => self.fg = Some((code - 90 + 8).to_string()),
+                100..=107 => self.bg = Some((code - 100 + 8).to_string()),
+                38 => self.fg = color(),
+                48 => self.bg = color(),
+                // Underline color has no tag, but its parameters still need to be skipped.
+                58 => {
+                    color();
+                }
+                39 => self.fg = None,
+                49 => self.bg = None,
+                _ => {}
+            }
+        }
+    }
+}
+
+fn base_color(n: u16) -> String {
+    match n {
+        0 => "black",
+        1 => "red",
+        2 => "green",
+        3 => "yellow",
+        4 => "blue",
+        5 => "magenta",
+        6 => "cyan",
+        // Different generators has different name for color 7, but index works for all of them.
+        _ => "7",
+    }
+    .to_owned()
+}
+
+fn index_color(n: &str) -> Option<String> {
+    n.parse::<u8>().ok().map(|n| n.to_string())
+}
+
+fn rgb_color(r: &str, g: &str, b: &str) -> Option<String> {
+    let [r, g, b] = [r, g, b].map(|c| c.parse::<u8>().ok());
+    Some(format!("{:02x}{:02x}{:02x}", r?, g?, b?))
+}
+
+/// Extended color in `;` separated form, like `38;5;n`, from parameters after the `38`.
+fn extended_color<'a>(params: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut next = || params.next().unwrap_or_default();
+    match next() {
+        "5" => index_color(next()),
+        "2" => {
+            let (r, g, b) = (next(), next(), next());
+            rgb_color(r, g, b)
+        }
+        _ => None,
+    }
+}
+
+/// Extended color in `:` separated form, like `38:5:n`, from sub-parameters after the `38`.
+fn sub_extended_color(subs: &[&str]) -> Option<String> {
+    match subs {
+        ["5", n, ..] => index_color(n),
+        // The colorspace id is often empty or omitted, like `38:2::r:g:b` or `38:2:r:g:b`.
+        ["2", _, r, g, b, ..] | ["2", r, g, b] => rgb_color(r, g, b),
+        _ => None,
+    }
+}
+
+/// Skip a escape sequence, `s` starts after the ESC character.
+///
+/// Returns SGR parameters if it's a SGR sequence, and the rest of input.
+fn escape_sequence(s: &str) -> (Option<&str>, &str) {
+    if let Some(csi) = s.strip_prefix('[') {
+        // parameter and intermediate bytes, until the final byte.
+        match csi.find(|c: char| ('\x40'..='\x7e').contains(&c)) {
+            Some(end) => {
+                let rest = &csi[end + 1..];
+                if csi[end..].starts_with('m') {
+                    (Some(&csi[..end]), rest)
+                } else {
+                    (None, rest)
+                }
+            }
+            None => (None, ""),
+        }
+    } else if let Some(osc) = s.strip_prefix(']') {
+        // terminated by BEL or ST(ESC \)
+        match osc.find(['\x07', '\x1b']) {
+            Some(end) if osc[end..].starts_with('\x07') => (None, &osc[end + 1..]),
+            Some(end) => (
+                None,
+                osc[end + 1..].strip_prefix('\\').unwrap_or(&osc[end + 1..]),
+            ),
+            None => (None, ""),
+        }
+    } else {
+        // intermediate bytes, then the final byte.
+        let rest = s.trim_start_matches(|c: char| ('\x20'..='\x2f').contains(&c));
+        let mut chars = rest.chars();
+        chars.next();
+        (None, chars.as_str())
+    }
+}
+
+/// Convert text contains ANSI escape sequences into markup source.
+///
+/// - SGR sequences (`ESC [ ... m`) are converted into elements, supports 16, 256 and true colors,
+///   in both `;` and `:` separated forms, and `b`, `d`, `i`, `u`, `sb`, `rb`, `r`, `h`, `s`
+///   modifiers, and their resets. All underline styles are converted to `u`, underline colors are
+///   ignored.
+/// - All other CSI and OSC sequences are stripped.
+/// - Style is kept across lines, like a terminal does.
+///
+/// The basic colors 0 to 6 are written in their names, other colors are written in index or hex
+/// format, so the result has same meaning for all builtin generators.
+///
+/// Adjacent text with same style is merged into one element.
+///
+/// ## Example
+///
+/// ```
+/// # use tui_markup::import::from_ansi;
+/// assert_eq!(
+///     from_ansi("\x1b[1;32mok\x1b[0m: 1 < 2\x1b]0;title\x07"),
+///     "<green,b ok>: 1 \\< 2"
+/// );
+/// ```
+///
+/// The result can be compiled by any generator:
+///
+/// ```
+/// # #[cfg(feature = "ratatui")] {
+/// # use ratatui::prelude::*;
+/// use tui_markup::{compile, generator::RatatuiTextGenerator, import::from_ansi};
+///
+/// let markup = from_ansi("\x1b[31merror\x1b[m");
+/// assert_eq!(
+///     compile::<RatatuiTextGenerator>(&markup),
+///     Ok(Text::from(Span::styled(
+///         "error",
+///         Style::default().fg(Color::Red)
+///     ))),
+/// );
+/// # }
+/// ```
+#[must_use]
+pub fn from_ansi(s: &str) -> String {
+    let mut output = MarkupWriter::default();
+    let mut state = State::default();
+
+    for (i, line) in s.lines().enumerate() {
+        if i > 0 {
+            output.new_line();
+        }
+
+        let mut rest = line;
+        while let Some(pos) = rest.find('\x1b') {
+            output.push(&rest[..pos], &state.tags());
+            let (sgr, remain) = escape_sequence(&rest[pos + 1..]);
+            if let Some(params) = sgr {
+                state.apply(params);
+            }
+            rest = remain;
+        }
+        output.push(rest, &state.tags());
+    }
+
+    output.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::from_ansi;
+
+    macro_rules! test_import {
+        ($ansi:expr => $markup:expr) => {
+            assert_eq!(from_ansi($ansi), $markup);
+        };
+    }
+
+    #[test]
+    fn test_plain_text() {
+        test_import!("" => "");
+        test_import!("plain" => "plain");
+        test_import!("a<b>\\c" => "a\\<b\\>\\\\c");
+        test_import!("one\r\ntwo\n" => "one\ntwo");
+    }
+
+    #[test]
+    fn test_basic_colors() {
+        test_import!("\x1b[31mred\x1b[0m" => "<red red>");
+        test_import!("\x1b[37;41mx\x1b[39;49m" => "<7,bg:red x>");
+        test_import!("\x1b[97;104mx" => "<15,bg:12 x>");
+    }
+
+    #[test]
+    fn test_extended_colors() {
+        test_import!("\x1b[38;5;208mx" => "<208 x>");
+        test_import!("\x1b[48;2;255;128;0mx" => "<bg:ff8000 x>");
+        test_import!("\x1b[38:2:102:204:255mx" => "<66ccff x>");
+    }
+
+    #[test]
+    fn test_sub_parameters() {
+        test_import!("\x1b[38:2::102:204:255mx" => "<66ccff x>");
+        test_import!("\x1b[48:2::1:2:3mx" => "<bg:010203 x>");
+        test_import!("\x1b[38:5:208;1mx" => "<208,b x>");
+        test_import!("\x1b[38:5:999;1mx" => "<b x>");
+    }
+
+    #[test]
+    fn test_underline_styles() {
+        test_import!("\x1b[4:3mx\x1b[4:0my" => "<u x>y");
+        test_import!("\x1b[4:1;3mx\x1b[24my" => "<i,u x><i y>");
+        test_import!("\x1b[1;21mx\x1b[22my" => "<b,u x><u y>");
+    }
+
+    #[test]
+    fn test_underline_color() {
+        test_import!("\x1b[58:2::1:2:3;31mx" => "<red x>");
+        test_import!("\x1b[58;5;1;1mx\x1b[59my" => "<b xy>");
+        test_import!("\x1b[58;2;1;2;3;4mx" => "<u x>");
+    }
+
+    #[test]
+    fn test_modifiers() {
+        test_import!("\x1b[1;3mbi\x1b[22mi\x1b[23m" => "<b,i bi><i i>");
+        test_import!("\x1b[4;5;6;7;8;9mx" => "<u,sb,rb,r,h,s x>");
+        test_import!("\x1b[2mx\x1b[mx" => "<d x>x");
+    }
+
+    #[test]
+    fn test_merge_same_style() {
+        test_import!("\x1b[31ma\x1b[31mb\x1b[1m\x1b[22mc" => "<red abc>");
+    }
+
+    #[test]
+    fn test_style_across_lines() {
+        test_import!("\x1b[32mone\ntwo\x1b[0m" => "<green one>\n<green two>");
+    }
+
+    #[test]
+    fn test_strip_other_sequences() {
+        test_import!("a\x1b[2Jb\x1b[1;1Hc" => "abc");
+        test_import!("a\x1b]52;c;aGVsbG8=\x07b\x1b]0;t\x1b\\c" => "abc");
+        test_import!("a\x1b(Bb\x1b[" => "ab");
+    }
+}
@@ -0,0 +1,141 @@
+use anstyle::{AnsiColor, Color, Effects, Style};
+
+use super::writer::MarkupWriter;
+use crate::generator::ansi::StyledText;
+
+const EFFECTS: [(Effects, &str); 8] = [
+    (Effects::BOLD, "b"),
+    (Effects::DIMMED, "d"),
+    (Effects::ITALIC, "i"),
+    (Effects::UNDERLINE, "u"),
+    (Effects::BLINK, "sb"),
+    (Effects::INVERT, "r"),
+    (Effects::HIDDEN, "h"),
+    (Effects::STRIKETHROUGH, "s"),
+];
+
+const UNDERLINES: [Effects; 4] = [
+    Effects::DOUBLE_UNDERLINE,
+    Effects::CURLY_UNDERLINE,
+    Effects::DOTTED_UNDERLINE,
+    Effects::DASHED_UNDERLINE,
+];
+
+fn color(c: Color) -> String {
+    match c {
+        Color::Ansi(c) => match c {
+            AnsiColor::Black => "black".to_owned(),
+            AnsiColor::Red => "red".to_owned(),
+            AnsiColor::Green => "green".to_owned(),
+            AnsiColor::Yellow => "yellow".to_owned(),
+            AnsiColor::Blue => "blue".to_owned(),
+            AnsiColor::Magenta => "magenta".to_owned(),
+            AnsiColor::Cyan => "cyan".to_owned(),
+            AnsiColor::White => "white".to_owned(),
+            // No name for bright colors, use the index which has same looking.
+            bright => (bright as u8).to_string(),
+        },
+        Color::Ansi256(c) => c.0.to_string(),
+        Color::Rgb(c) => format!("{:02x}{:02x}{:02x}", c.0, c.1, c.2),
+    }
+}
+
+fn tags(style: &Style) -> String {
+    let mut tags = Vec::new();
+    tags.extend(style.get_fg_color().map(color));
+    tags.extend(style.get_bg_color().map(|c| format!("bg:{}", color(c))));
+
+    let mut effects = style.get_effects();
+    if UNDERLINES.iter().any(|u| effects.contains(*u)) {
+        effects = effects.insert(Effects::UNDERLINE);
+    }
+    tags.extend(
+        EFFECTS
+            .iter()
+            .filter(|(e, _)| effects.contains(*e))
+            .map(|(_, s)| (*s).to_owned()),
+    );
+
+    tags.join(",")
+}
+
+/// Convert a [`StyledText`] into markup source for
+/// [`ANSIStringsGenerator`][crate::generator::ANSIStringsGenerator].
+///
+/// Colors are written in the shortest spelling, adjacent spans with same style are merged.
+///
+/// Bright ANSI colors are written in index, all kinds of underline are written as `u`, and
+/// underline color is ignored, because the tag convertor does not support them.
+///
+/// ## Example
+///
+/// ```
+/// use anstyle::{AnsiColor, Style};
+/// use tui_markup::{
+///     generator::ansi::{StyledSpan, StyledText},
+///     import::from_anstyle,
+/// };
+///
+/// let green = Style::new().fg_color(Some(AnsiColor::Green.into()));
+/// let text = StyledText::new(vec![
+///     StyledSpan::new(Style::new(), "1 < "),
+///     StyledSpan::new(green, "2"),
+///     StyledSpan::new(green, "3"),
+/// ]);
+///
+/// assert_eq!(from_anstyle(&text), "1 \\< <green 23>");
+/// ```
+#[must_use]
+pub fn from_anstyle(text: &StyledText<'_>) -> String {
+    let mut output = MarkupWriter::default();
+
+    for span in text.spans() {
+        output.push(span.text(), &tags(span.style()));
+    }
+
+    output.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use anstyle::{Ansi256Color, AnsiColor, Effects, Style};
+
+    use super::from_anstyle;
+    use crate::{
+        compile,
+        generator::{
+            ANSIStringsGenerator,
+            ansi::{StyledSpan, StyledText},
+        },
+    };
+
+    #[test]
+    fn test_shortest_spelling() {
+        let text = StyledText::new(vec![
+            StyledSpan::new(
+                Style::new().fg_color(Some(AnsiColor::BrightRed.into())),
+                "a",
+            ),
+            StyledSpan::new(Style::new().bg_color(Some(Ansi256Color(208).into())), "b"),
+            StyledSpan::new(
+                Style::new().effects(Effects::BOLD | Effects::BLINK | Effects::CURLY_UNDERLINE),
+                "c",
+            ),
+        ]);
+
+        assert_eq!(from_anstyle(&text), "<9 a><bg:208 b><b,u,sb c>");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = "<bg:blue one <green two <b,i three>>> \\\\ <208 four>\n<66ccff five>";
+        let text = compile::<ANSIStringsGenerator>(source).unwrap();
+        let markup = from_anstyle(&text);
+        assert_eq!(
+            compile::<ANSIStringsGenerator>(&markup)
+                .unwrap()
+                .to_string(),
+            text.to_string()
+        );
+    }
+}
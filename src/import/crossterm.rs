@@ -0,0 +1,124 @@
+use std::fmt::Display;
+
+use crossterm::style::{Attribute, Color, ContentStyle, StyledContent};
+
+use super::writer::MarkupWriter;
+
+const ATTRIBUTES: [(Attribute, &str); 9] = [
+    (Attribute::Bold, "b"),
+    (Attribute::Dim, "d"),
+    (Attribute::Italic, "i"),
+    (Attribute::Underlined, "u"),
+    (Attribute::SlowBlink, "sb"),
+    (Attribute::RapidBlink, "rb"),
+    (Attribute::Reverse, "r"),
+    (Attribute::Hidden, "h"),
+    (Attribute::CrossedOut, "s"),
+];
+
+fn color(c: Color) -> Option<String> {
+    Some(
+        match c {
+            Color::Reset => return None,
+            Color::Black => "black",
+            Color::DarkRed => "red",
+            Color::DarkGreen => "green",
+            Color::DarkYellow => "yellow",
+            Color::DarkBlue => "blue",
+            Color::DarkMagenta => "magenta",
+            Color::DarkCyan => "cyan",
+            Color::Grey => "gray",
+            Color::DarkGrey => "gray+",
+            Color::Red => "red-",
+            Color::Green => "green-",
+            Color::Yellow => "yellow-",
+            Color::Blue => "blue-",
+            Color::Magenta => "magenta-",
+            Color::Cyan => "cyan-",
+            Color::White => "white",
+            Color::Rgb { r, g, b } => return Some(format!("{r:02x}{g:02x}{b:02x}")),
+            Color::AnsiValue(n) => return Some(n.to_string()),
+        }
+        .to_owned(),
+    )
+}
+
+fn tags(style: &ContentStyle) -> String {
+    let mut tags = Vec::new();
+    tags.extend(style.foreground_color.and_then(color));
+    tags.extend(
+        style
+            .background_color
+            .and_then(color)
+            .map(|c| format!("bg:{c}")),
+    );
+    tags.extend(
+        ATTRIBUTES
+            .iter()
+            .filter(|(a, _)| style.attributes.has(*a))
+            .map(|(_, s)| (*s).to_owned()),
+    );
+    tags.join(",")
+}
+
+/// Convert a series of crossterm [`StyledContent`] into markup source for
+/// [`CrosstermCommandsGenerator`][crate::generator::CrosstermCommandsGenerator].
+///
+/// Colors are written in the shortest spelling, adjacent contents with same style are merged.
+///
+/// [`Color::Reset`], underline color and attributes which has no tag are ignored.
+///
+/// ## Example
+///
+/// ```
+/// use crossterm::style::{ContentStyle, Stylize};
+/// use tui_markup::import::from_crossterm;
+///
+/// let contents = ["1 < ".reset(), "2".dark_green(), "3".dark_green()];
+///
+/// assert_eq!(from_crossterm(&contents), "1 \\< <green 23>");
+/// ```
+#[must_use]
+pub fn from_crossterm<'s, D, I>(contents: I) -> String
+where
+    D: Display + 's,
+    I: IntoIterator<Item = &'s StyledContent<D>>,
+{
+    let mut output = MarkupWriter::default();
+
+    for content in contents {
+        output.push(&content.content().to_string(), &tags(content.style()));
+    }
+
+    output.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::style::{Attribute, Color, ContentStyle, Stylize};
+
+    use super::from_crossterm;
+
+    #[test]
+    fn test_shortest_spelling() {
+        let contents = [
+            "a".red(),
+            "b".on(Color::AnsiValue(208)),
+            ContentStyle::new()
+                .with(Color::Rgb {
+                    r: 0x66,
+                    g: 0xcc,
+                    b: 0xff,
+                })
+                .apply("c"),
+            "d".attribute(Attribute::Bold)
+                .attribute(Attribute::SlowBlink),
+            "\ne".dark_grey(),
+        ];
+
+        assert_eq!(
+            from_crossterm(&contents),
+            "<red- a><bg:208 b><66ccff c><b,sb d>\n<gray+ e>"
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! Import text from other formats into markup source.
+
+mod ansi;
+#[cfg(feature = "ansi")]
+mod anstyle;
+#[cfg(feature = "crossterm")]
+mod crossterm;
+#[cfg(feature = "ratatui")]
+mod ratatui;
+mod writer;
+
+pub use ansi::from_ansi;
+
+#[cfg(feature = "ansi")]
+pub use self::anstyle::from_anstyle;
+#[cfg(feature = "crossterm")]
+pub use self::crossterm::from_crossterm;
+#[cfg(feature = "ratatui")]
+pub use self::ratatui::from_ratatui;
@@ -0,0 +1,144 @@
+use ratatui_core::{
+    style::{Color, Modifier, Style},
+    text::Text,
+};
+
+use super::writer::MarkupWriter;
+
+const MODIFIERS: [(Modifier, &str); 9] = [
+    (Modifier::BOLD, "b"),
+    (Modifier::DIM, "d"),
+    (Modifier::ITALIC, "i"),
+    (Modifier::UNDERLINED, "u"),
+    (Modifier::SLOW_BLINK, "sb"),
+    (Modifier::RAPID_BLINK, "rb"),
+    (Modifier::REVERSED, "r"),
+    (Modifier::HIDDEN, "h"),
+    (Modifier::CROSSED_OUT, "s"),
+];
+
+fn color(c: Color) -> Option<String> {
+    Some(
+        match c {
+            Color::Reset => return None,
+            Color::Black => "black",
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+            Color::Gray => "gray",
+            Color::DarkGray => "gray+",
+            Color::LightRed => "red-",
+            Color::LightGreen => "green-",
+            Color::LightYellow => "yellow-",
+            Color::LightBlue => "blue-",
+            Color::LightMagenta => "magenta-",
+            Color::LightCyan => "cyan-",
+            Color::White => "white",
+            Color::Rgb(r, g, b) => return Some(format!("{r:02x}{g:02x}{b:02x}")),
+            Color::Indexed(n) => return Some(n.to_string()),
+        }
+        .to_owned(),
+    )
+}
+
+fn tags(style: Style) -> String {
+    let mut tags = Vec::new();
+    tags.extend(style.fg.and_then(color));
+    tags.extend(style.bg.and_then(color).map(|c| format!("bg:{c}")));
+    tags.extend(
+        MODIFIERS
+            .iter()
+            .filter(|(m, _)| style.add_modifier.contains(*m))
+            .map(|(_, s)| (*s).to_owned()),
+    );
+    tags.join(",")
+}
+
+/// Convert a `ratatui` [Text] into markup source for
+/// [`RatatuiTextGenerator`][crate::generator::RatatuiTextGenerator].
+///
+/// Style of the text and lines are patched into each span. Colors are written in the shortest
+/// spelling, adjacent spans with same style are merged.
+///
+/// [`Color::Reset`], removed modifiers, underline color and line alignment can't be written in
+/// markup, they are ignored.
+///
+/// ## Example
+///
+/// ```
+/// use ratatui::prelude::*;
+/// use tui_markup::import::from_ratatui;
+///
+/// let text = Text::from(Line::from(vec![
+///     Span::raw("1 < "),
+///     Span::styled("2", Style::default().fg(Color::Green)),
+///     Span::styled("3", Style::default().fg(Color::Green)),
+/// ]));
+///
+/// assert_eq!(from_ratatui(&text), "1 \\< <green 23>");
+/// ```
+#[must_use]
+pub fn from_ratatui(text: &Text<'_>) -> String {
+    let mut output = MarkupWriter::default();
+
+    for (i, line) in text.lines.iter().enumerate() {
+        if i > 0 {
+            output.new_line();
+        }
+
+        let line_style = text.style.patch(line.style);
+        for span in &line.spans {
+            output.push(&span.content, &tags(line_style.patch(span.style)));
+        }
+    }
+
+    output.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui_core::{
+        style::{Color, Modifier, Style},
+        text::{Line, Span, Text},
+    };
+
+    use super::from_ratatui;
+    use crate::{compile, generator::RatatuiTextGenerator};
+
+    #[test]
+    fn test_shortest_spelling() {
+        let text = Text::from(Line::from(vec![
+            Span::styled("a", Style::default().fg(Color::LightRed)),
+            Span::styled("b", Style::default().bg(Color::Indexed(208))),
+            Span::styled("c", Style::default().fg(Color::Rgb(0x66, 0xcc, 0xff))),
+            Span::styled(
+                "d",
+                Style::default().add_modifier(Modifier::BOLD | Modifier::RAPID_BLINK),
+            ),
+        ]));
+
+        assert_eq!(from_ratatui(&text), "<red- a><bg:208 b><66ccff c><b,rb d>");
+    }
+
+    #[test]
+    fn test_line_and_text_style() {
+        let text = Text::from(vec![
+            Line::styled("one", Style::default().fg(Color::Red)),
+            Line::from(vec![Span::raw("<"), Span::raw(">")]),
+        ])
+        .style(Style::default().bg(Color::Blue));
+
+        assert_eq!(from_ratatui(&text), "<red,bg:blue one>\n<bg:blue \\<\\>>");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = "<bg:blue one <green two <b,i three>>> \\\\ <gray+ four>\n<66ccff five>";
+        let text = compile::<RatatuiTextGenerator>(source).unwrap();
+        let markup = from_ratatui(&text);
+        assert_eq!(compile::<RatatuiTextGenerator>(&markup).unwrap(), text);
+    }
+}
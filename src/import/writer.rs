@@ -0,0 +1,61 @@
+use alloc::{borrow::ToOwned, string::String};
+use core::fmt::Write;
+
+use crate::generator::helper::escape;
+
+/// Markup source writer, which merges adjacent text with same tags into one element.
+#[derive(Debug, Default)]
+pub(super) struct MarkupWriter {
+    result: String,
+    text: String,
+    tags: String,
+}
+
+impl MarkupWriter {
+    /// Append text with a comma separated tag list, `\n` in text starts a new line.
+    pub fn push(&mut self, text: &str, tags: &str) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.new_line();
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if tags != self.tags {
+                self.flush();
+                tags.clone_into(&mut self.tags);
+            }
+
+            self.text.push_str(line);
+        }
+    }
+
+    /// Start a new line.
+    pub fn new_line(&mut self) {
+        self.flush();
+        self.result.push('\n');
+    }
+
+    /// Get the final markup source.
+    pub fn finish(mut self) -> String {
+        self.flush();
+        self.result
+    }
+
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        if self.tags.is_empty() {
+            self.result.push_str(&escape(&self.text));
+        } else {
+            // Write into String never fails.
+            let _ = write!(self.result, "<{} {}>", self.tags, escape(&self.text));
+        }
+
+        self.text.clear();
+    }
+}
@@ -1,5 +1,6 @@
 #![forbid(unsafe_code)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 //! # tui markup
 //!
 //! This crate provides a markup language to
@@ -36,6 +37,10 @@
 //! | `ansi`      | Direct print into stdout when using an asni compatible terminal | [`ANSIStringsGenerator`][generator::ANSIStringsGenerator]             |
 //! | `ratatui`   | Integrated with the [ratatui] crate                             | [`RatatuiTextGenerator`][generator::RatatuiTextGenerator]             |
 //! | `crossterm` | Integrated with [crossterm] crate                               | [`CrosstermCommandsGenerator`][generator::CrosstermCommandsGenerator] |
+//! | `cursive`   | Integrated with [cursive] crate                                 | [`CursiveStyledStringGenerator`][generator::CursiveStyledStringGenerator] |
+//! | `clap`      | Styled help text of the [clap] crate                            | [`ClapStyledStrGenerator`][generator::ClapStyledStrGenerator]         |
+//! | `console`   | Integrated with [console] crate, used by `indicatif`/`dialoguer` | [`ConsoleStyledObjectsGenerator`][generator::ConsoleStyledObjectsGenerator] |
+//! | `nu-ansi-term` | Integrated with [nu-ansi-term] crate, used by `reedline`     | [`NuAnsiTermGenerator`][generator::NuAnsiTermGenerator]               |
 //!
 //! The example screenshot above is using the `ratatui` generator, print in
 //! Windows Terminal.
@@ -43,18 +48,70 @@
 //! If you want write your own generator, please checkout documents of
 //! [Generator] trait.
 //!
+//! ### `no_std`
+//!
+//! The parser, tag conversion and generator helpers only need `alloc`. Disable the default `std`
+//! feature to use them in `no_std` environment. Builtin generators, [`document`] and [`cache`]
+//! modules, and `std::error::Error` impls of error types, require `std`.
+//!
+//! ### Untrusted input
+//!
+//! Nesting depth of elements is always limited, so deep nested source can't overflow the stack.
+//! For markup from plugins, chat messages or network, use [`compile_with_limits`] or
+//! [`parser::parse_with_limits`] with stricter [`parser::Limits`] of tag count, line length and
+//! source size.
+//!
+//! Control characters and bidi overrides in plain text are replaced with visible forms by all
+//! builtin generators, see [`generator::helper::Sanitize`]. To restrict which tags users can use,
+//! check the AST by a [`generator::TagPolicy`] before tag conversion.
+//!
 //! [docs/syntax.ebnf]: https://github.com/7sDream/tui-markup/blob/master/docs/syntax.ebnf
 //! [help-text-screenshot]: https://rikka.7sdre.am/files/ee68d36d-b1e7-4575-bb13-e37ba7ead044.png
 //! [examples/help.txt]: https://github.com/7sDream/tui-markup/blob/master/examples/help.txt
 //! [ratatui]: https://docs.rs/ratatui/latest/ratatui/
+//! [crossterm]: https://docs.rs/crossterm/latest/crossterm/
+//! [cursive]: https://docs.rs/cursive/latest/cursive/
+//! [clap]: https://docs.rs/clap/latest/clap/
+//! [console]: https://docs.rs/console/latest/console/
+//! [nu-ansi-term]: https://docs.rs/nu-ansi-term/latest/nu_ansi_term/
+
+extern crate alloc;
 
 mod error;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod document;
 pub mod generator;
+pub mod import;
 pub mod parser;
+pub mod printer;
+pub mod template;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
+#[cfg(test)]
+mod test_util;
+
+#[doc(hidden)]
+pub mod __private {
+    //! Re-exports for code generated by `tui-markup-macros`, not a public API.
+
+    #[cfg(feature = "ansi")]
+    pub use anstyle;
+    #[cfg(feature = "crossterm")]
+    pub use crossterm;
+    #[cfg(feature = "ratatui")]
+    pub use ratatui_core;
+}
 
 pub use error::{Error, LocatedError};
-pub use generator::Generator;
-use generator::TagConvertor;
+use generator::{
+    Buffered, TagConvertor,
+    helper::{SourceMap, SourceMapGenerator},
+};
+pub use generator::{Generator, StreamGenerator};
+use parser::Limits;
 
 /// Parse markup language source, then generate final output using the default
 /// configure of a generator type.
@@ -74,19 +131,99 @@ where
 /// Parse markup language source, then generate final output using the provided
 /// generator.
 ///
+/// Source is parsed and converted line by line like [`compile_stream_with`], lines are generated
+/// together by the [`Buffered`] adapter.
+///
 /// See document of generator type for examples.
 ///
 /// ## Errors
 ///
 /// If input source contains invalid syntax or generator failed.
-pub fn compile_with<'a, G>(s: &'a str, mut generator: G) -> Result<G::Output, Error<'a, G::Err>>
+pub fn compile_with<'a, G>(s: &'a str, generator: G) -> Result<G::Output, Error<'a, G::Err>>
 where
     G: Generator<'a>,
+{
+    compile_with_limits(s, generator, Limits::default())
+}
+
+/// Same as [`compile_with`], but parse source with [`Limits`], for untrusted markup.
+///
+/// ## Errors
+///
+/// If input source contains invalid syntax, exceeds any limit, or generator failed.
+pub fn compile_with_limits<'a, G>(
+    s: &'a str, generator: G, limits: Limits,
+) -> Result<G::Output, Error<'a, G::Err>>
+where
+    G: Generator<'a>,
+{
+    drive(
+        parser::parse_lines_with_limits(s, limits),
+        Buffered::new(generator),
+    )
+}
+
+/// Convert and send parsed lines into a [`StreamGenerator`], then get it's output.
+fn drive<'a, G>(
+    lines: impl Iterator<Item = Result<alloc::vec::Vec<parser::Item<'a>>, parser::Error<'a>>>,
+    mut generator: G,
+) -> Result<G::Output, Error<'a, G::Err>>
+where
+    G: StreamGenerator<'a>,
+{
+    for line in lines {
+        generator::convert_and_generate_line(&mut generator, line?).map_err(Into::into)?;
+    }
+    generator.finish().map_err(Into::into)
+}
+
+/// Parse markup language source line by line, and drive a [`StreamGenerator`] using it's default
+/// configure.
+///
+/// ## Errors
+///
+/// If input source contains invalid syntax or generator failed.
+pub fn compile_stream<'a, G>(s: &'a str) -> Result<G::Output, Error<'a, G::Err>>
+where
+    G: StreamGenerator<'a> + Default,
+{
+    compile_stream_with(s, G::default())
+}
+
+/// Parse markup language source line by line, and drive the provided [`StreamGenerator`].
+///
+/// Each line is sent to the generator as soon as it's parsed. If a line has invalid syntax, lines
+/// before it are already sent.
+///
+/// ## Errors
+///
+/// If input source contains invalid syntax or generator failed.
+pub fn compile_stream_with<'a, G>(s: &'a str, generator: G) -> Result<G::Output, Error<'a, G::Err>>
+where
+    G: StreamGenerator<'a>,
+{
+    drive(parser::parse_lines(s), generator)
+}
+
+/// Same as [`compile_with`], and also create a [`SourceMap`] from output spans back to markup
+/// source.
+///
+/// Only works for generators which implement [`SourceMapGenerator`].
+///
+/// ## Errors
+///
+/// If input source contains invalid syntax or generator failed.
+pub fn compile_with_source_map<'a, G>(
+    s: &'a str, mut generator: G,
+) -> Result<(G::Output, SourceMap<'a>), Error<'a, G::Err>>
+where
+    G: SourceMapGenerator<'a>,
 {
     let ast = parser::parse(s)?;
     let ir = generator.convertor().convert_ast(ast);
-    match generator.generate(ir) {
-        Ok(result) => Ok(result),
+    let mut map = SourceMap::new(s);
+    match generator.generate_with_source_map(ir, &mut map) {
+        Ok(result) => Ok((result, map)),
         Err(err) => Err(err.into()),
     }
 }
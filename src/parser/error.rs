@@ -1,11 +1,14 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 use winnow::{
     error::{AddContext, ParserError},
-    stream::{LocatingSlice, Offset},
+    stream::{LocatingSlice, Offset, Stream},
 };
 
-use crate::{error::LocatedError, parser::LSpan};
+use crate::{
+    error::LocatedError,
+    parser::{LSpan, Limit},
+};
 
 /// Kind of parse error.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -16,6 +19,8 @@ pub enum ErrorKind {
     UnescapableChar,
     /// Element not closed but reaches line end.
     ElementNotClose,
+    /// Source exceeds one of the [`Limits`][super::Limits].
+    LimitExceeded(Limit),
 }
 
 /// Error type for [parse][super::parse].
@@ -29,12 +34,18 @@ pub struct Error<'a> {
 }
 
 impl Display for Error<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_str(match self.kind {
             Some(kind) => match kind {
                 ErrorKind::UnescapedChar => "unescaped character",
                 ErrorKind::UnescapableChar => "unescapable character",
                 ErrorKind::ElementNotClose => "expect '>' to close element for element starter",
+                ErrorKind::LimitExceeded(limit) => match limit {
+                    Limit::Depth => "nesting depth limit exceeded",
+                    Limit::Tags => "tag count limit exceeded",
+                    Limit::LineLength => "line length limit exceeded",
+                    Limit::Size => "source size limit exceeded",
+                },
             },
             None => "unknown error",
         })?;
@@ -48,6 +59,7 @@ impl Display for Error<'_> {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error<'_> {}
 
 impl<'a> Error<'a> {
@@ -70,7 +82,14 @@ impl<'a> Error<'a> {
         self
     }
 
-    /// Set the line number for this error.
+    /// Create a error of `kind`, at byte `offset` of a `line` with `source`.
+    pub(crate) fn at(line: usize, source: &'a str, offset: usize, kind: ErrorKind) -> Self {
+        let mut input = LSpan::new(source);
+        input.next_slice(offset);
+        Self::new(&input).with_line(line).with_kind(kind)
+    }
+
+    /// Set the error kind.
     pub(crate) fn with_kind(mut self, kind: ErrorKind) -> Self {
         self.kind = Some(kind);
         self
@@ -0,0 +1,233 @@
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+
+use crate::parser::{Error, Item, line_at, line_starts, parse_line};
+
+/// A text edit: a byte range of old source replaced by new text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit<'t> {
+    /// Replaced byte range in old source.
+    pub range: Range<usize>,
+    /// Text inserted at start of the range.
+    pub text: &'t str,
+}
+
+impl<'t> Edit<'t> {
+    /// Create a edit which replaces `range` with `text`.
+    #[must_use]
+    pub fn new(range: Range<usize>, text: &'t str) -> Self {
+        Self { range, text }
+    }
+
+    /// Apply this edit to a source.
+    ///
+    /// ## Panics
+    ///
+    /// If range is out of bounds or not on char boundaries of the source.
+    #[must_use]
+    pub fn apply(&self, source: &str) -> String {
+        let mut result = String::with_capacity(source.len() - self.range.len() + self.text.len());
+        result.push_str(&source[..self.range.start]);
+        result.push_str(self.text);
+        result.push_str(&source[self.range.end..]);
+        result
+    }
+}
+
+/// Result of [`reparse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reparsed<'a> {
+    /// Updated AST of new source.
+    pub ast: Vec<Vec<Item<'a>>>,
+    /// Lines in the new AST which are reparsed, all other lines are the same as before the edit.
+    pub changed: Range<usize>,
+}
+
+/// Lines of old and new source which may be changed by the edit.
+///
+/// Lines in the grammar are independent, so only lines touched by the edit need to be reparsed.
+/// If a construct can span lines, the ranges should be expanded to its boundaries here.
+fn affected(old: &[usize], new: &[usize], edit: &Edit<'_>) -> (Range<usize>, Range<usize>) {
+    let line_of =
+        |starts: &[usize], pos: usize| starts.partition_point(|&s| s <= pos).saturating_sub(1);
+
+    let first = line_of(old, edit.range.start);
+    let old_last = line_of(old, edit.range.end);
+    let new_last = line_of(new, edit.range.start + edit.text.len());
+
+    (
+        first.min(old.len())..(old_last + 1).min(old.len()),
+        first.min(new.len())..(new_last + 1).min(new.len()),
+    )
+}
+
+/// Move a slice of old source to the same text in new source, which is `shift` bytes after it.
+fn rebase<'a>(old: &str, new: &'a str, s: &str, shift: isize) -> &'a str {
+    let offset = (s.as_ptr() as usize)
+        .checked_sub(old.as_ptr() as usize)
+        .filter(|offset| offset + s.len() <= old.len())
+        .expect("old ast should be parsed from old source");
+    let start = offset
+        .checked_add_signed(shift)
+        .expect("shift is in bounds");
+    &new[start..start + s.len()]
+}
+
+fn rebase_item<'a>(old: &str, new: &'a str, item: Item<'_>, shift: isize) -> Item<'a> {
+    match item {
+        Item::PlainText(t) => Item::PlainText(rebase(old, new, t, shift)),
+        Item::Element(tags, children) => Item::Element(
+            tags.into_iter()
+                .map(|t| rebase(old, new, t, shift))
+                .collect(),
+            children
+                .into_iter()
+                .map(|c| rebase_item(old, new, c, shift))
+                .collect(),
+        ),
+    }
+}
+
+/// Update AST after a edit of source, only lines touched by the edit are reparsed.
+///
+/// `old_ast` must be the result of [`parse`][super::parse] of `old_source`, and `new_source` must
+/// be the result of applying `edit` to `old_source`, see [`Edit::apply`]. Unchanged lines in the
+/// old AST are moved to borrow from new source without parsing them again.
+///
+/// Returned [`Reparsed::changed`] is the range of lines in new AST which are reparsed, it can be
+/// used to update a cache of outputs, for example.
+///
+/// ## Errors
+///
+/// If any reparsed line has invalid syntax, error location is in new source.
+///
+/// ## Panics
+///
+/// If `old_ast` does not borrow from `old_source`, or length of `new_source` does not match the
+/// edit.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::parser::{Edit, parse, reparse};
+///
+/// let source = "<b one>\n<i two>\nthree";
+/// let ast = parse(source).unwrap();
+///
+/// let edit = Edit::new(9..10, "u");
+/// let new_source = edit.apply(source);
+/// let reparsed = reparse(source, ast, &edit, &new_source).unwrap();
+///
+/// assert_eq!(reparsed.changed, 1..2);
+/// assert_eq!(reparsed.ast, parse(&new_source).unwrap());
+/// ```
+pub fn reparse<'a>(
+    old_source: &str, old_ast: Vec<Vec<Item<'_>>>, edit: &Edit<'_>, new_source: &'a str,
+) -> Result<Reparsed<'a>, Error<'a>> {
+    assert_eq!(
+        new_source.len(),
+        old_source.len() - edit.range.len() + edit.text.len(),
+        "new source should be old source with edit applied"
+    );
+
+    let old_starts = line_starts(old_source);
+    let new_starts = line_starts(new_source);
+    debug_assert_eq!(old_starts.len(), old_ast.len());
+
+    let (old_lines, new_lines) = affected(&old_starts, &new_starts, edit);
+    #[allow(clippy::cast_possible_wrap)] // a str's len never exceeds isize::MAX
+    let shift = new_source.len() as isize - old_source.len() as isize;
+
+    let mut ast = Vec::with_capacity(new_starts.len());
+    let mut old_ast = old_ast.into_iter();
+
+    for line in old_ast.by_ref().take(old_lines.start) {
+        ast.push(
+            line.into_iter()
+                .map(|item| rebase_item(old_source, new_source, item, 0))
+                .collect(),
+        );
+    }
+
+    for index in new_lines.clone() {
+        let line = line_at(new_source, &new_starts, index).unwrap_or_default();
+        ast.push(parse_line(index, line)?);
+    }
+
+    for line in old_ast.skip(old_lines.len()) {
+        ast.push(
+            line.into_iter()
+                .map(|item| rebase_item(old_source, new_source, item, shift))
+                .collect(),
+        );
+    }
+
+    Ok(Reparsed {
+        ast,
+        changed: new_lines,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Edit, reparse};
+    use crate::parser::parse;
+
+    fn check(source: &str, edit: Edit<'_>) {
+        let old_ast = parse(source).unwrap();
+        let new_source = edit.apply(source);
+        let reparsed = reparse(source, old_ast.clone(), &edit, &new_source).unwrap();
+
+        assert_eq!(reparsed.ast, parse(&new_source).unwrap(), "{edit:?}");
+
+        let unchanged_after = reparsed.ast.len() - reparsed.changed.end;
+        assert_eq!(
+            reparsed.ast[..reparsed.changed.start],
+            old_ast[..reparsed.changed.start]
+        );
+        assert_eq!(
+            reparsed.ast[reparsed.changed.end..],
+            old_ast[old_ast.len() - unchanged_after..]
+        );
+    }
+
+    #[test]
+    fn test_all_edits() {
+        let source = "<b one>\r\n\n<i two <u x>>\nthree\n";
+        let texts = ["", "a", "\n", "<b ", ">", "c\nd\r\n"];
+        for start in 0..=source.len() {
+            for end in start..=source.len() {
+                for text in texts {
+                    let edit = Edit::new(start..end, text);
+                    if parse(&edit.apply(source)).is_ok() {
+                        check(source, edit);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let source = "a\nb\nc";
+        let edit = Edit::new(2..3, "x\ny");
+        let new_source = edit.apply(source);
+        let reparsed = reparse(source, parse(source).unwrap(), &edit, &new_source).unwrap();
+        assert_eq!(reparsed.changed, 1..3);
+
+        let edit = Edit::new(0..0, "");
+        let reparsed = reparse(source, parse(source).unwrap(), &edit, source).unwrap();
+        assert_eq!(reparsed.changed, 0..1);
+    }
+
+    #[test]
+    fn test_error_location() {
+        use crate::LocatedError;
+
+        let source = "a\nb\nc";
+        let edit = Edit::new(4..5, "<x");
+        let new_source = edit.apply(source);
+        let error = reparse(source, parse(source).unwrap(), &edit, &new_source).unwrap_err();
+        assert_eq!(error.location(), (3, 1));
+    }
+}
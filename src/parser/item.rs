@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::generator::{Tag, TagG};
 
 /// AST item.
@@ -0,0 +1,72 @@
+/// Which one of [`Limits`] is exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// [`Limits::max_depth`].
+    Depth,
+    /// [`Limits::max_tags`].
+    Tags,
+    /// [`Limits::max_line_len`].
+    LineLength,
+    /// [`Limits::max_size`].
+    Size,
+}
+
+/// Limits of markup source, for parse untrusted input safely.
+///
+/// Parsing, tag conversion and many helpers of AST work recursively on nested elements, so the
+/// nesting depth is always limited, [`parse`][super::parse] uses the default value, and no limits
+/// allow nesting deeper than [`Limits::MAX_DEPTH`]. Other limits are not set by default, use
+/// [`parse_with_limits`][super::parse_with_limits] or
+/// [`compile_with_limits`][crate::compile_with_limits] with a stricter one for input from plugins,
+/// chat messages or network.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::parser::{ErrorKind, Limit, Limits, parse_with_limits};
+///
+/// let limits = Limits {
+///     max_depth: 2,
+///     ..Limits::default()
+/// };
+///
+/// assert!(parse_with_limits("<b <i two>>", limits).is_ok());
+///
+/// let error = parse_with_limits("<b <i <u three>>>", limits).unwrap_err();
+/// assert_eq!(error.kind(), Some(ErrorKind::LimitExceeded(Limit::Depth)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// Maximum nesting depth of elements, a top level element has depth 1.
+    ///
+    /// Values larger than [`Limits::MAX_DEPTH`] are treated as it.
+    pub max_depth: usize,
+    /// Maximum number of tags of each element.
+    pub max_tags: usize,
+    /// Maximum length of each line in bytes, without line ending.
+    pub max_line_len: usize,
+    /// Maximum length of whole source in bytes.
+    pub max_size: usize,
+}
+
+impl Limits {
+    /// Default value of [`Limits::max_depth`].
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+    /// Hard maximum of [`Limits::max_depth`].
+    ///
+    /// Error reporting, tag conversion, the printer, [`Visit`][super::Visit] and
+    /// [`Fold`][super::Fold] all recurse on nested elements, this depth is safe for them even in
+    /// debug builds on a thread with 2 MiB stack, the default for spawned threads.
+    pub const MAX_DEPTH: usize = 256;
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_tags: usize::MAX,
+            max_line_len: usize::MAX,
+            max_size: usize::MAX,
+        }
+    }
+}
@@ -1,7 +1,12 @@
 //! Parsing stage of the compilation process.
 
+use alloc::{vec, vec::Vec};
+
 pub use error::{Error, ErrorKind};
+pub use incremental::{Edit, Reparsed, reparse};
 pub use item::{Item, ItemC, ItemG};
+pub use limits::{Limit, Limits};
+pub use visit::{Fold, Visit, VisitMut, fold, visit, visit_mut};
 use winnow::{
     ModalResult, Parser,
     ascii::take_escaped,
@@ -11,10 +16,17 @@ use winnow::{
     token::{one_of, take_till, take_while},
 };
 
-use crate::parser::ErrorKind::UnescapedChar;
+use crate::parser::{
+    ErrorKind::UnescapedChar,
+    scan::{Scanner, Stop},
+};
 
 mod error;
+mod incremental;
 mod item;
+mod limits;
+mod scan;
+mod visit;
 
 #[cfg(test)]
 mod test;
@@ -113,7 +125,8 @@ fn output<'i>(i: &mut LSpan<'i>) -> ParseResult<'i, Vec<Item<'i>>> {
     Ok(result)
 }
 
-fn parse_line(line: usize, i: &str) -> Result<Vec<Item<'_>>, Error<'_>> {
+/// Parse a line by the grammar, it's slower than [`Scanner`] but reports detailed error.
+fn parse_line_grammar(line: usize, i: &str) -> Result<Vec<Item<'_>>, Error<'_>> {
     let mut located = LSpan::new(i);
 
     output
@@ -125,38 +138,115 @@ fn parse_line(line: usize, i: &str) -> Result<Vec<Item<'_>>, Error<'_>> {
         .parse_next(&mut located)
 }
 
+pub(crate) fn parse_line(line: usize, i: &str) -> Result<Vec<Item<'_>>, Error<'_>> {
+    scan_line(&mut Scanner::new(Limits::default()), line, i)
+}
+
+fn scan_line<'a>(
+    scanner: &mut Scanner<'a>, line: usize, i: &'a str,
+) -> Result<Vec<Item<'a>>, Error<'a>> {
+    match scanner.line(i) {
+        Ok(items) => Ok(items),
+        Err(Stop::Syntax) => parse_line_grammar(line, i),
+        Err(Stop::Limit(limit, offset)) => {
+            Err(Error::at(line, i, offset, ErrorKind::LimitExceeded(limit)))
+        }
+    }
+}
+
+/// Error for source longer than `max_size`, located at the first byte after the limit.
+///
+/// If that byte is a line ending, the location is the end of that line.
+fn size_error(s: &str, max_size: usize) -> Error<'_> {
+    let mut offset = max_size;
+    while !s.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let line = s[..offset].matches('\n').count();
+    let start = s[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let source = s[start..].lines().next().unwrap_or_default();
+
+    let offset = (offset - start).min(source.len());
+    Error::at(line, source, offset, ErrorKind::LimitExceeded(Limit::Size))
+}
+
 /// Parse tui markup source into ast.
 ///
+/// Nesting depth of elements is limited by the default [`Limits`].
+///
 /// ## Errors
 ///
 /// If input source has invalid syntax.
 pub fn parse(s: &str) -> Result<Vec<Vec<Item<'_>>>, Error<'_>> {
-    s.lines()
-        .enumerate()
-        .map(|(i, line)| parse_line(i, line))
-        .collect()
+    parse_with_limits(s, Limits::default())
+}
+
+/// Parse tui markup source into ast, with [`Limits`] for untrusted input.
+///
+/// ## Errors
+///
+/// If input source has invalid syntax, or exceeds any limit, with kind
+/// [`ErrorKind::LimitExceeded`].
+pub fn parse_with_limits(s: &str, limits: Limits) -> Result<Vec<Vec<Item<'_>>>, Error<'_>> {
+    parse_lines_with_limits(s, limits).collect()
 }
 
-fn hex_digit() -> impl ContainsToken<char> {
-    ('0'..='9', 'A'..='F', 'a'..='f')
+/// Parse tui markup source lazily, line by line.
+///
+/// Each line is parsed only when the iterator reaches it, so memory usage does not grow with the
+/// whole source. Errors has the same location info as [`parse`].
+pub fn parse_lines(s: &str) -> impl Iterator<Item = Result<Vec<Item<'_>>, Error<'_>>> {
+    parse_lines_with_limits(s, Limits::default())
 }
 
-fn hex_color_part(i: &mut &str) -> ModalResult<u8> {
-    repeat(2..=2, one_of(hex_digit()))
-        .map(|_: ()| ())
-        .take()
-        .try_map(|x| u8::from_str_radix(x, 16))
-        .parse_next(i)
+/// Same as [`parse_lines`], with [`Limits`] for untrusted input.
+///
+/// If source exceeds [`Limits::max_size`], the only item is the error.
+pub fn parse_lines_with_limits(
+    s: &str, limits: Limits,
+) -> impl Iterator<Item = Result<Vec<Item<'_>>, Error<'_>>> {
+    let too_large = (s.len() > limits.max_size).then(|| size_error(s, limits.max_size));
+    let lines = if too_large.is_some() { "" } else { s };
+
+    let mut scanner = Scanner::new(limits);
+    too_large.map(Err).into_iter().chain(
+        lines
+            .lines()
+            .enumerate()
+            .map(move |(i, line)| scan_line(&mut scanner, i, line)),
+    )
+}
+
+/// Byte offsets of start of each line, lines are split in the same way as [`str::lines`].
+pub(crate) fn line_starts(s: &str) -> Vec<usize> {
+    let mut starts = vec![];
+    let mut start = 0;
+    while start < s.len() {
+        starts.push(start);
+        match s[start..].find('\n') {
+            Some(end) => start += end + 1,
+            None => break,
+        }
+    }
+    starts
+}
+
+/// Source of a line by index into result of [`line_starts`], without line ending.
+pub(crate) fn line_at<'a>(s: &'a str, starts: &[usize], index: usize) -> Option<&'a str> {
+    let line = &s[*starts.get(index)?..];
+    Some(match line.find('\n') {
+        Some(end) => line[..end].strip_suffix('\r').unwrap_or(&line[..end]),
+        None => line,
+    })
 }
 
 /// Parse string of 6 hex digit into r, g, b value.
 pub fn hex_rgb(s: &str) -> Option<(u8, u8, u8)> {
-    let (r, g, b) = (hex_color_part, hex_color_part, hex_color_part)
-        .parse(s)
-        .ok()?;
-
-    // After all three hex pairs are parsed, verify nothing remains.
-    // Since hex_color_part takes 2 hex chars, the input advances by 6 chars.
-    // If the input was exactly 6 hex chars, the remaining is empty.
-    Some((r, g, b))
+    if s.len() != 6 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let part = |i: usize| u8::from_str_radix(&s[i..i + 2], 16).ok();
+    Some((part(0)?, part(2)?, part(4)?))
 }
@@ -0,0 +1,145 @@
+//! Hand written scanner for the success path of parsing.
+//!
+//! It accepts exactly the same syntax as the winnow grammar in parent module, and produces the same
+//! AST, but without backtracking and error bookkeeping. On any syntax error it gives up and returns
+//! [`Stop::Syntax`], then the grammar parses the line again for a detailed error.
+//!
+//! [`Limits`] are checked here, so the recursive grammar never parses elements nested deeper than
+//! the limit.
+
+use alloc::vec::Vec;
+
+use crate::parser::{Item, Limit, Limits};
+
+/// Why the scanner stops before line end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Stop {
+    /// There is a syntax error.
+    Syntax,
+    /// A limit is exceeded at the byte offset.
+    Limit(Limit, usize),
+}
+
+fn is_special(b: u8) -> bool {
+    matches!(b, b'<' | b'>' | b'\\')
+}
+
+fn is_tag_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b':' | b'+' | b'-')
+}
+
+/// End of the plain text starts at `pos`, which must not be `<` or `>`.
+fn plain_text_end(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        match bytes[pos..].iter().position(|&b| is_special(b)) {
+            None => return Some(bytes.len()),
+            Some(offset) => pos += offset,
+        }
+
+        if bytes[pos] != b'\\' {
+            return Some(pos);
+        }
+
+        if !bytes.get(pos + 1).copied().is_some_and(is_special) {
+            return None;
+        }
+
+        pos += 2;
+    }
+}
+
+/// Scanner of lines, it can be reused for many lines to avoid allocation of its buffers.
+///
+/// Items and tags are collected into shared buffers first, and moved into a vector of exact size
+/// when an element closes. So each vector in the AST is allocated once, and small ones are cheap
+/// to free.
+#[derive(Debug, Default)]
+pub(super) struct Scanner<'a> {
+    /// Items of all open elements and the line, from outer to inner.
+    items: Vec<Item<'a>>,
+    /// Tags of each open element, with start index of its items in `items`.
+    open: Vec<(Vec<&'a str>, usize)>,
+    /// Tags of the element being scanned.
+    tags: Vec<&'a str>,
+    limits: Limits,
+}
+
+impl<'a> Scanner<'a> {
+    pub(super) fn new(mut limits: Limits) -> Self {
+        limits.max_depth = limits.max_depth.min(Limits::MAX_DEPTH);
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Tag list starts at `pos`, after the `<`. Returns position after the space.
+    fn tag_list(&mut self, s: &'a str, mut pos: usize) -> Result<usize, Stop> {
+        let bytes = s.as_bytes();
+        self.tags.clear();
+
+        loop {
+            let len = bytes[pos..].iter().take_while(|&&b| is_tag_byte(b)).count();
+            if len == 0 {
+                return Err(Stop::Syntax);
+            }
+            if self.tags.len() >= self.limits.max_tags {
+                return Err(Stop::Limit(Limit::Tags, pos));
+            }
+            self.tags.push(&s[pos..pos + len]);
+            pos += len;
+
+            match bytes.get(pos) {
+                Some(b',') => pos += 1,
+                Some(b' ') => return Ok(pos + 1),
+                _ => return Err(Stop::Syntax),
+            }
+        }
+    }
+
+    /// Parse a line, stops at the first syntax error or exceeded limit.
+    pub(super) fn line(&mut self, s: &'a str) -> Result<Vec<Item<'a>>, Stop> {
+        let bytes = s.as_bytes();
+        self.items.clear();
+        self.open.clear();
+
+        if s.len() > self.limits.max_line_len {
+            let mut end = self.limits.max_line_len;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            return Err(Stop::Limit(Limit::LineLength, end));
+        }
+
+        let mut pos = 0;
+        while pos < bytes.len() {
+            match bytes[pos] {
+                b'<' => {
+                    let start = pos;
+                    pos = self.tag_list(s, pos + 1)?;
+                    if self.open.len() >= self.limits.max_depth {
+                        return Err(Stop::Limit(Limit::Depth, start));
+                    }
+                    self.open.push((self.tags.to_vec(), self.items.len()));
+                }
+                b'>' => {
+                    let (tags, start) = self.open.pop().ok_or(Stop::Syntax)?;
+                    let children = self.items.drain(start..).collect();
+                    self.items.push(Item::Element(tags, children));
+                    pos += 1;
+                }
+                _ => {
+                    let end = plain_text_end(bytes, pos).ok_or(Stop::Syntax)?;
+                    self.items.push(Item::PlainText(&s[pos..end]));
+                    pos = end;
+                }
+            }
+        }
+
+        if self.open.is_empty() {
+            Ok(self.items.drain(..).collect())
+        } else {
+            Err(Stop::Syntax)
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use super::{ErrorKind, Item};
+use proptest::prelude::*;
+
+use super::{ErrorKind, Item, Limit, Limits, parse, parse_lines_with_limits, parse_with_limits};
+use crate::{error::LocatedError, generator::helper::escape};
 
 macro_rules! test_ok {
     ($s:expr $(, $item:expr)*) => {
@@ -150,3 +153,210 @@ fn test_custom_color() {
         Item::Element(vec!["bg:ff8000", "66ccff"], vec![Item::PlainText("text")])
     );
 }
+
+#[test]
+fn test_scanner_same_as_grammar() {
+    const ALPHABET: [&str; 6] = ["<", ">", "\\", "b", " ", ","];
+
+    let mut sources = vec![String::new()];
+    let mut last = sources.clone();
+    for _ in 0..6 {
+        last = last
+            .iter()
+            .flat_map(|s| ALPHABET.map(|c| format!("{s}{c}")))
+            .collect();
+        sources.extend(last.iter().cloned());
+    }
+
+    // Reuse one scanner, so a failed line must not affect the next one
+    let mut scanner = super::scan::Scanner::default();
+    for source in sources
+        .iter()
+        .map(String::as_str)
+        .chain(["é<b,x: 中\\\\>", "<b é>"])
+    {
+        assert_eq!(
+            scanner.line(source).ok(),
+            super::parse_line_grammar(0, source).ok(),
+            "{source:?}"
+        );
+    }
+}
+
+#[test]
+fn test_hex_rgb() {
+    use super::hex_rgb;
+
+    assert_eq!(hex_rgb("ff8000"), Some((0xff, 0x80, 0x00)));
+    assert_eq!(hex_rgb("66CCff"), Some((0x66, 0xcc, 0xff)));
+    for s in ["", "ff800", "ff80000", "+f8000", "gg0000", "ff 800", "中中"] {
+        assert_eq!(hex_rgb(s), None, "{s:?}");
+    }
+}
+
+fn limit_error(s: &str, limits: Limits) -> (ErrorKind, (usize, usize)) {
+    let e = parse_with_limits(s, limits).unwrap_err();
+    (e.kind().unwrap(), e.location())
+}
+
+#[test]
+fn test_depth_limit() {
+    let nested = |n: usize| "<b ".repeat(n) + "x" + &">".repeat(n);
+
+    let max = Limits::DEFAULT_MAX_DEPTH;
+    assert!(parse(&nested(max)).is_ok());
+    assert_eq!(
+        limit_error(&nested(max + 1), Limits::default()),
+        (ErrorKind::LimitExceeded(Limit::Depth), (1, max * 3 + 1))
+    );
+
+    // Too deep to parse recursively, closed or not
+    for source in [nested(100_000), "<b ".repeat(100_000)] {
+        assert_eq!(
+            limit_error(&source, Limits::default()),
+            (ErrorKind::LimitExceeded(Limit::Depth), (1, max * 3 + 1))
+        );
+    }
+
+    let limits = Limits {
+        max_depth: 0,
+        ..Limits::default()
+    };
+    assert!(parse_with_limits("a\\<", limits).is_ok());
+    assert_eq!(
+        limit_error("a<b x>", limits),
+        (ErrorKind::LimitExceeded(Limit::Depth), (1, 2))
+    );
+}
+
+#[test]
+fn test_syntax_error_within_depth_limit() {
+    let limits = Limits {
+        max_depth: 2,
+        ..Limits::default()
+    };
+    assert_eq!(
+        limit_error("<b <i x\\y>>", limits),
+        (ErrorKind::ElementNotClose, (1, 1))
+    );
+    assert_eq!(
+        limit_error("<b <i <u x\\y>>>", limits),
+        (ErrorKind::LimitExceeded(Limit::Depth), (1, 7))
+    );
+}
+
+#[test]
+fn test_depth_limit_clamped() {
+    let limits = Limits {
+        max_depth: usize::MAX,
+        ..Limits::default()
+    };
+    let nested = |n: usize| "<b ".repeat(n) + "x\\y" + &">".repeat(n);
+
+    let max = Limits::MAX_DEPTH;
+    assert_eq!(
+        limit_error(&nested(max), limits),
+        (ErrorKind::ElementNotClose, (1, 1))
+    );
+    for n in [max + 1, 5000, 20000] {
+        assert_eq!(
+            limit_error(&nested(n), limits),
+            (ErrorKind::LimitExceeded(Limit::Depth), (1, max * 3 + 1))
+        );
+    }
+}
+
+#[test]
+fn test_tags_limit() {
+    let limits = Limits {
+        max_tags: 2,
+        ..Limits::default()
+    };
+    assert!(parse_with_limits("<b,i x><u,red y>", limits).is_ok());
+    assert_eq!(
+        limit_error("<b x><b,i,u y>", limits),
+        (ErrorKind::LimitExceeded(Limit::Tags), (1, 11))
+    );
+}
+
+#[test]
+fn test_line_length_limit() {
+    let limits = Limits {
+        max_line_len: 4,
+        ..Limits::default()
+    };
+    assert!(parse_with_limits("abcd\r\nefgh\n", limits).is_ok());
+    assert_eq!(
+        limit_error("abcd\nabcde", limits),
+        (ErrorKind::LimitExceeded(Limit::LineLength), (2, 5))
+    );
+    // Location is at the start of the character which exceeds the limit
+    assert_eq!(
+        limit_error("abc中", limits),
+        (ErrorKind::LimitExceeded(Limit::LineLength), (1, 4))
+    );
+}
+
+#[test]
+fn test_size_limit() {
+    let limits = |max_size| Limits {
+        max_size,
+        ..Limits::default()
+    };
+    assert!(parse_with_limits("ab\ncd", limits(5)).is_ok());
+
+    let cases = [
+        ("ab\ncde", 5, (2, 3)),
+        ("ab中", 3, (1, 3)),
+        ("ab\r\ncd", 2, (1, 3)),
+        ("ab\r\ncd", 3, (1, 3)),
+        ("ab\r\ncd", 4, (2, 1)),
+        ("\n\n\n", 1, (2, 1)),
+    ];
+    for (source, max_size, location) in cases {
+        assert_eq!(
+            limit_error(source, limits(max_size)),
+            (ErrorKind::LimitExceeded(Limit::Size), location),
+            "{source:?}"
+        );
+    }
+
+    let mut lines = parse_lines_with_limits("a\nb", limits(1));
+    assert!(lines.next().unwrap().is_err());
+    assert!(lines.next().is_none());
+}
+
+#[test]
+fn test_limit_error_message() {
+    let source = "<b ".repeat(100);
+    let e = parse(&source).unwrap_err();
+    assert_eq!(e.to_string(), "nesting depth limit exceeded '<' near 1:193");
+
+    let e = parse_with_limits("ab\n", Limits {
+        max_size: 2,
+        ..Limits::default()
+    })
+    .unwrap_err();
+    assert_eq!(e.to_string(), "source size limit exceeded near 1:3");
+}
+
+proptest! {
+    #[test]
+    fn test_escaped_text_is_plain_text(s in "[^\r\n]+") {
+        let escaped = escape(&s);
+        prop_assert_eq!(
+            crate::parser::parse_line(0, &escaped),
+            Ok(vec![Item::PlainText(&escaped)])
+        );
+    }
+
+    #[test]
+    fn test_error_location_is_in_source(s in "[a-z0-9 <>\\\\,:\n]{0,24}|\\PC{0,24}") {
+        if let Err(e) = crate::parser::parse(&s) {
+            let (line, column) = e.location();
+            let text = s.lines().nth(line - 1).expect("line of error should exist");
+            prop_assert!(column >= 1 && text.is_char_boundary(column - 1), "{:?}", e);
+            prop_assert!(column - 1 < text.len(), "{:?}", e);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use alloc::{vec, vec::Vec};
+
+use crate::parser::Item;
+
+/// Read-only traversal over a line of AST items.
+///
+/// Works for both raw item ([`Item<&str>`][Item]) and item after tag conversion
+/// ([`ItemC`][crate::parser::ItemC]).
+///
+/// Every hook receives the tag stack, which is tag lists of all enclosing elements, the outermost
+/// one first. The stack given to `enter_element` and `exit_element` does not contain the element
+/// itself.
+///
+/// All hooks do nothing by default, use [`visit`] to drive the traversal.
+///
+/// ## Example
+///
+/// Find max nesting depth of a line:
+///
+/// ```
+/// use tui_markup::parser::{Visit, parse, visit};
+///
+/// #[derive(Default)]
+/// struct Depth(usize);
+///
+/// impl<'a> Visit<'a, &'a str> for Depth {
+///     fn enter_element(&mut self, _tags: &[&'a str], stack: &[&[&'a str]]) {
+///         self.0 = self.0.max(stack.len() + 1);
+///     }
+/// }
+///
+/// let ast = parse("<b one <i two <u three>>> <s four>").unwrap();
+/// let mut depth = Depth::default();
+/// visit(&ast[0], &mut depth);
+/// assert_eq!(depth.0, 3);
+/// ```
+pub trait Visit<'a, T> {
+    /// Called for each plain text, the text is still escaped.
+    fn visit_plain_text(&mut self, _text: &'a str, _stack: &[&[T]]) {}
+
+    /// Called before children of a element are visited.
+    fn enter_element(&mut self, _tags: &[T], _stack: &[&[T]]) {}
+
+    /// Called after children of a element are visited.
+    fn exit_element(&mut self, _tags: &[T], _stack: &[&[T]]) {}
+}
+
+/// Mutable traversal over a line of AST items.
+///
+/// Same as [`Visit`], but tags can be changed in `enter_element`, before children are visited, so
+/// children will see the changed tags in tag stack. Plain text can also be replaced.
+///
+/// All hooks do nothing by default, use [`visit_mut`] to drive the traversal.
+///
+/// ## Example
+///
+/// Rename a tag:
+///
+/// ```
+/// use tui_markup::{
+///     parser::{VisitMut, parse, visit_mut},
+///     printer::print_line,
+/// };
+///
+/// struct Rename;
+///
+/// impl<'a> VisitMut<'a, &'a str> for Rename {
+///     fn enter_element(&mut self, tags: &mut Vec<&'a str>, _stack: &[&[&'a str]]) {
+///         for tag in tags {
+///             if *tag == "warn" {
+///                 *tag = "yellow";
+///             }
+///         }
+///     }
+/// }
+///
+/// let mut ast = parse("<warn,b careful>").unwrap();
+/// visit_mut(&mut ast[0], &mut Rename);
+/// assert_eq!(print_line(&ast[0]), "<yellow,b careful>");
+/// ```
+pub trait VisitMut<'a, T> {
+    /// Called for each plain text, the text is still escaped.
+    fn visit_plain_text(&mut self, _text: &mut &'a str, _stack: &[&[T]]) {}
+
+    /// Called before children of a element are visited.
+    fn enter_element(&mut self, _tags: &mut Vec<T>, _stack: &[&[T]]) {}
+
+    /// Called after children of a element are visited.
+    fn exit_element(&mut self, _tags: &[T], _stack: &[&[T]]) {}
+}
+
+/// Owned transformation of a line of AST items, which can change the tag type.
+///
+/// Tags are folded in pre-order, so the tag stack given to every hook contains already folded tags
+/// of all enclosing elements, the outermost one first.
+///
+/// Use [`fold`] to drive the transformation.
+///
+/// ## Example
+///
+/// Keep only modifier tags, and drop elements which become useless:
+///
+/// ```
+/// use tui_markup::{
+///     parser::{Fold, fold, parse},
+///     printer::print_line,
+/// };
+///
+/// struct OnlyModifiers;
+///
+/// impl<'a> Fold<'a, &'a str> for OnlyModifiers {
+///     type Tag = &'a str;
+///
+///     fn fold_tag(&mut self, tag: &'a str, _stack: &[Vec<&'a str>]) -> Option<&'a str> {
+///         tag.starts_with("mod:").then_some(tag)
+///     }
+/// }
+///
+/// let ast = parse("<green one> <mod:b,red two>").unwrap();
+/// let line = fold(ast.into_iter().next().unwrap(), &mut OnlyModifiers);
+/// assert_eq!(print_line(&line), "one <mod:b two>");
+/// ```
+pub trait Fold<'a, T> {
+    /// Tag type of the result.
+    type Tag;
+
+    /// Fold a tag of element, return `None` to remove it.
+    fn fold_tag(&mut self, tag: T, stack: &[Vec<Self::Tag>]) -> Option<Self::Tag>;
+
+    /// Fold a plain text, return `None` to remove it.
+    fn fold_plain_text(
+        &mut self, text: &'a str, _stack: &[Vec<Self::Tag>],
+    ) -> Option<Item<'a, Self::Tag>> {
+        Some(Item::PlainText(text))
+    }
+
+    /// Fold a element after all it's tags and children are folded, return `None` to remove it.
+    fn fold_element(
+        &mut self, tags: Vec<Self::Tag>, children: Vec<Item<'a, Self::Tag>>,
+        _stack: &[Vec<Self::Tag>],
+    ) -> Option<Item<'a, Self::Tag>> {
+        Some(Item::Element(tags, children))
+    }
+}
+
+fn visit_items<'a, 't, T, V>(items: &'t [Item<'a, T>], stack: &mut Vec<&'t [T]>, v: &mut V)
+where
+    V: Visit<'a, T> + ?Sized,
+{
+    for item in items {
+        match item {
+            Item::PlainText(t) => v.visit_plain_text(t, stack),
+            Item::Element(tags, children) => {
+                v.enter_element(tags, stack);
+                stack.push(tags);
+                visit_items(children, stack, v);
+                stack.pop();
+                v.exit_element(tags, stack);
+            }
+        }
+    }
+}
+
+/// Visit a line of items with a [`Visit`] implementation.
+pub fn visit<'a, T, V>(line: &[Item<'a, T>], visitor: &mut V)
+where
+    V: Visit<'a, T> + ?Sized,
+{
+    visit_items(line, &mut vec![], visitor);
+}
+
+fn visit_items_mut<'a, 't, T, V>(items: &'t mut [Item<'a, T>], stack: &mut Vec<&'t [T]>, v: &mut V)
+where
+    V: VisitMut<'a, T> + ?Sized,
+{
+    for item in items {
+        match item {
+            Item::PlainText(t) => v.visit_plain_text(t, stack),
+            Item::Element(tags, children) => {
+                v.enter_element(tags, stack);
+                let tags: &'t [T] = tags;
+                stack.push(tags);
+                visit_items_mut(children, stack, v);
+                stack.pop();
+                v.exit_element(tags, stack);
+            }
+        }
+    }
+}
+
+/// Visit a line of items with a [`VisitMut`] implementation.
+pub fn visit_mut<'a, T, V>(line: &mut [Item<'a, T>], visitor: &mut V)
+where
+    V: VisitMut<'a, T> + ?Sized,
+{
+    visit_items_mut(line, &mut vec![], visitor);
+}
+
+fn fold_items<'a, T, F>(
+    items: Vec<Item<'a, T>>, stack: &mut Vec<Vec<F::Tag>>, f: &mut F,
+) -> Vec<Item<'a, F::Tag>>
+where
+    F: Fold<'a, T> + ?Sized,
+{
+    let mut result = Vec::with_capacity(items.len());
+
+    for item in items {
+        let folded = match item {
+            Item::PlainText(t) => f.fold_plain_text(t, stack),
+            Item::Element(tags, children) => {
+                let mut folded_tags = Vec::with_capacity(tags.len());
+                for tag in tags {
+                    if let Some(tag) = f.fold_tag(tag, stack) {
+                        folded_tags.push(tag);
+                    }
+                }
+
+                stack.push(folded_tags);
+                let children = fold_items(children, stack, f);
+                let folded_tags = stack.pop().unwrap_or_default();
+
+                f.fold_element(folded_tags, children, stack)
+            }
+        };
+
+        match folded {
+            Some(Item::Element(tags, children)) if tags.is_empty() => result.extend(children),
+            Some(item) => result.push(item),
+            None => {}
+        }
+    }
+
+    result
+}
+
+/// Fold a line of items with a [`Fold`] implementation.
+///
+/// Element without any tag in result is replaced by it's children, because it can't be written in
+/// markup and has no effect.
+pub fn fold<'a, T, F>(line: Vec<Item<'a, T>>, folder: &mut F) -> Vec<Item<'a, F::Tag>>
+where
+    F: Fold<'a, T> + ?Sized,
+{
+    fold_items(line, &mut vec![], folder)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Fold, Visit, VisitMut, fold, visit, visit_mut};
+    use crate::parser::{Item, parse};
+
+    #[derive(Default)]
+    struct Events(Vec<String>);
+
+    impl<'a> Visit<'a, &'a str> for Events {
+        fn visit_plain_text(&mut self, text: &'a str, stack: &[&[&'a str]]) {
+            self.0.push(format!("{text}@{stack:?}"));
+        }
+
+        fn enter_element(&mut self, tags: &[&'a str], stack: &[&[&'a str]]) {
+            self.0.push(format!("enter {tags:?}@{stack:?}"));
+        }
+
+        fn exit_element(&mut self, tags: &[&'a str], stack: &[&[&'a str]]) {
+            self.0.push(format!("exit {tags:?}@{stack:?}"));
+        }
+    }
+
+    #[test]
+    fn test_visit_order_and_stack() {
+        let ast = parse("a<b b<i,u c>>").unwrap();
+        let mut events = Events::default();
+        visit(&ast[0], &mut events);
+        assert_eq!(events.0, vec![
+            "a@[]",
+            "enter [\"b\"]@[]",
+            "b@[[\"b\"]]",
+            "enter [\"i\", \"u\"]@[[\"b\"]]",
+            "c@[[\"b\"], [\"i\", \"u\"]]",
+            "exit [\"i\", \"u\"]@[[\"b\"]]",
+            "exit [\"b\"]@[]",
+        ]);
+    }
+
+    struct Redact;
+
+    impl<'a> VisitMut<'a, &'a str> for Redact {
+        fn visit_plain_text(&mut self, text: &mut &'a str, stack: &[&[&'a str]]) {
+            if stack.iter().any(|tags| tags.contains(&"secret")) {
+                *text = "***";
+            }
+        }
+
+        fn enter_element(&mut self, tags: &mut Vec<&'a str>, _stack: &[&[&'a str]]) {
+            if tags.contains(&"password") {
+                tags.push("secret");
+            }
+        }
+    }
+
+    #[test]
+    fn test_visit_mut_sees_changed_tags() {
+        let mut ast = parse("<password <b abc>> def").unwrap();
+        visit_mut(&mut ast[0], &mut Redact);
+        assert_eq!(ast[0], vec![
+            Item::Element(vec!["password", "secret"], vec![Item::Element(
+                vec!["b"],
+                vec![Item::PlainText("***")]
+            )]),
+            Item::PlainText(" def"),
+        ]);
+    }
+
+    struct Length;
+
+    impl<'a> Fold<'a, &'a str> for Length {
+        type Tag = usize;
+
+        fn fold_tag(&mut self, tag: &'a str, stack: &[Vec<usize>]) -> Option<usize> {
+            (tag != "drop").then_some(tag.len() + stack.len() * 100)
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        let ast = parse("<red a<drop b>><drop c>").unwrap();
+        let line = fold(ast.into_iter().next().unwrap(), &mut Length);
+        assert_eq!(line, vec![
+            Item::Element(vec![3], vec![Item::PlainText("a"), Item::PlainText("b")]),
+            Item::PlainText("c"),
+        ]);
+    }
+}
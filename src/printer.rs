@@ -0,0 +1,409 @@
+//! Print AST back into markup source, and canonical formatter based on it.
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{
+    generator::{
+        TagConvertor,
+        helper::{escape, unescape},
+    },
+    parser::{self, Item},
+};
+
+fn print_plain_text(result: &mut String, escaped: &str) {
+    // Unescape then escape again, so even a hand written invalid plain text is printed correctly.
+    for s in unescape(escaped) {
+        result.push_str(&escape(s));
+    }
+}
+
+fn print_item(result: &mut String, item: &Item<'_>) {
+    match item {
+        Item::PlainText(t) => print_plain_text(result, t),
+        Item::Element(tags, children) => {
+            // Element without tag has no effect, and can't be written in markup.
+            if !tags.is_empty() {
+                result.push('<');
+                result.push_str(&tags.join(","));
+                result.push(' ');
+            }
+            children.iter().for_each(|child| print_item(result, child));
+            if !tags.is_empty() {
+                result.push('>');
+            }
+        }
+    }
+}
+
+/// Print a line of AST items back into markup source.
+#[must_use]
+pub fn print_line(line: &[Item<'_>]) -> String {
+    let mut result = String::new();
+    line.iter().for_each(|item| print_item(&mut result, item));
+    result
+}
+
+/// Print AST back into markup source.
+///
+/// Plain text is re-escaped, so the result is always valid markup source, and parse it will get the
+/// same AST back.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::{parser::parse, printer::print};
+///
+/// let source = "<bg:blue one <green two>> \\<three\\>\nfour";
+/// assert_eq!(print(&parse(source).unwrap()), source);
+/// ```
+#[must_use]
+pub fn print(ast: &[Vec<Item<'_>>]) -> String {
+    ast.iter()
+        .map(|line| print_line(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Canonical form of a item, text is stored unescaped.
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Element(Vec<String>, Vec<Node>),
+}
+
+fn push_node(nodes: &mut Vec<Node>, node: Node) {
+    match (nodes.last_mut(), node) {
+        (Some(Node::Text(last)), Node::Text(t)) => last.push_str(&t),
+        (Some(Node::Element(last_tags, last_children)), Node::Element(tags, children))
+            if *last_tags == tags =>
+        {
+            children
+                .into_iter()
+                .for_each(|child| push_node(last_children, child));
+        }
+        (_, node) => nodes.push(node),
+    }
+}
+
+/// Keep the last occurrence of each tag, later tag overrides earlier one, so it's not changed.
+fn dedup_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.into_iter().rev() {
+        if !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result.reverse();
+    result
+}
+
+fn normalize_tag<'a, C: TagConvertor<'a>>(convertor: &mut C, tag: &str) -> String {
+    if convertor.parse_custom_tag(tag).is_some() {
+        return tag.to_owned();
+    }
+
+    // value is a shorter spelling only if it does not become a custom tag, and it's parsed in the
+    // same way when no type is given.
+    let shorter = |convertor: &mut C, value: &str, is_color: bool| {
+        convertor.parse_custom_tag(value).is_none()
+            && convertor.parse_color(value).is_some() == is_color
+    };
+
+    match tag.split_once(':') {
+        Some(("fg" | "", value)) if shorter(convertor, value, true) => value.to_owned(),
+        Some(("mod", value))
+            if convertor.parse_modifier(value).is_some() && shorter(convertor, value, false) =>
+        {
+            value.to_owned()
+        }
+        Some(("", value))
+            if convertor.parse_modifier(value).is_some() && shorter(convertor, value, false) =>
+        {
+            value.to_owned()
+        }
+        _ => tag.to_owned(),
+    }
+}
+
+fn canonical_items<'a, C: TagConvertor<'a>>(convertor: &mut C, items: Vec<Item<'_>>) -> Vec<Node> {
+    let mut nodes = Vec::new();
+
+    for item in items {
+        match item {
+            Item::PlainText(t) => push_node(&mut nodes, Node::Text(unescape(t).collect())),
+            Item::Element(tags, children) => {
+                let mut children = canonical_items(convertor, children);
+                if children.is_empty() {
+                    continue;
+                }
+
+                let mut tags: Vec<_> = tags
+                    .into_iter()
+                    .map(|tag| normalize_tag(convertor, tag))
+                    .collect();
+
+                // The only child is a element, merge it's tags into this one.
+                if children.len() == 1
+                    && let Some(Node::Element(..)) = children.first()
+                    && let Some(Node::Element(inner_tags, inner_children)) = children.pop()
+                {
+                    tags.extend(inner_tags);
+                    children = inner_children;
+                }
+
+                if tags.is_empty() {
+                    children
+                        .into_iter()
+                        .for_each(|child| push_node(&mut nodes, child));
+                } else {
+                    push_node(&mut nodes, Node::Element(dedup_tags(tags), children));
+                }
+            }
+        }
+    }
+
+    nodes
+}
+
+fn print_node(result: &mut String, node: &Node) {
+    match node {
+        Node::Text(t) => result.push_str(&escape(t)),
+        Node::Element(tags, children) => {
+            // Write into String never fails.
+            let _ = write!(result, "<{} ", tags.join(","));
+            children.iter().for_each(|child| print_node(result, child));
+            result.push('>');
+        }
+    }
+}
+
+/// Format markup source into canonical form, using tag definitions of a tag convertor.
+///
+/// - Builtin tags are written in the shortest spelling, like `fg:green` to `green`, `mod:b` to `b`,
+///   unless the short one is a custom tag or has different meaning.
+/// - Duplicated tags in one element are removed.
+/// - Element which only contains another element is merged into one, like `<b <i x>>` to `<b,i x>`.
+/// - Adjacent elements with same tags are merged, like `<b x><b y>` to `<b xy>`.
+/// - Empty elements are removed.
+/// - Plain text is escaped in the minimal way.
+///
+/// Tags the convertor does not understand are kept as-is. The final output of a generator using
+/// this convertor is not changed by formatting.
+///
+/// ## Errors
+///
+/// If input source has invalid syntax.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ratatui")] {
+/// use tui_markup::{generator::ratatui::RatatuiTagConvertor, printer::format};
+///
+/// let mut convertor = <RatatuiTagConvertor>::default();
+/// assert_eq!(
+///     format("<fg:green <mod:b,b x>><green,b y><i > z", &mut convertor),
+///     Ok("<green,b xy> z".to_owned())
+/// );
+/// # }
+/// ```
+pub fn format<'a, C: TagConvertor<'a>>(
+    source: &'a str, convertor: &mut C,
+) -> Result<String, parser::Error<'a>> {
+    let ast = parser::parse(source)?;
+
+    let mut result = String::with_capacity(source.len());
+    for (i, line) in ast.into_iter().enumerate() {
+        if i > 0 {
+            result.push('\n');
+        }
+        canonical_items(convertor, line)
+            .iter()
+            .for_each(|node| print_node(&mut result, node));
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::{print, print_line};
+    use crate::{
+        generator::helper::escape,
+        parser::{Item, parse},
+    };
+
+    #[test]
+    fn test_print_round_trip() {
+        for source in [
+            "",
+            "plain",
+            "\\<\\>\\\\",
+            "<b >",
+            "<bg:cyan <yellow one> two>",
+            "<bg:magenta,gray,mod:u,x text>\n\n<:white a\\<b>",
+        ] {
+            assert_eq!(print(&parse(source).unwrap()), source);
+        }
+    }
+
+    /// Owned AST node for proptest, text is stored escaped.
+    #[derive(Debug, Clone)]
+    enum Node {
+        Text(String),
+        Element(Vec<String>, Vec<Node>),
+    }
+
+    fn item(node: &Node) -> Item<'_> {
+        match node {
+            Node::Text(t) => Item::PlainText(t),
+            Node::Element(tags, children) => Item::Element(
+                tags.iter().map(String::as_str).collect(),
+                children.iter().map(item).collect(),
+            ),
+        }
+    }
+
+    fn items(ast: &[Vec<Node>]) -> Vec<Vec<Item<'_>>> {
+        ast.iter()
+            .map(|line| line.iter().map(item).collect())
+            .collect()
+    }
+
+    /// Merge adjacent texts, as parser always outputs the longest plain text.
+    fn merge_texts(nodes: Vec<Node>) -> Vec<Node> {
+        let mut result: Vec<Node> = vec![];
+        for node in nodes {
+            match (result.last_mut(), node) {
+                (Some(Node::Text(last)), Node::Text(t)) => last.push_str(&t),
+                (_, node) => result.push(node),
+            }
+        }
+        result
+    }
+
+    /// Unescaped text of a line, special chars are more likely than others.
+    fn text() -> impl Strategy<Value = String> {
+        prop_oneof!["[a-z <>\\\\,:]{1,8}", "[^\r\n]{1,8}"]
+    }
+
+    fn tags(min: usize) -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec("[a-zA-Z0-9:+-]{1,6}", min..4)
+    }
+
+    /// AST in the form parser outputs: texts are escaped and not empty, adjacent texts are merged,
+    /// elements have tags, and last line is not empty.
+    fn canonical_ast() -> impl Strategy<Value = Vec<Vec<Node>>> {
+        let node = text()
+            .prop_map(|t| Node::Text(escape(&t).into_owned()))
+            .prop_recursive(4, 32, 4, |inner| {
+                (tags(1), prop::collection::vec(inner, 0..4))
+                    .prop_map(|(tags, children)| Node::Element(tags, merge_texts(children)))
+            });
+        let line = prop::collection::vec(node, 0..6).prop_map(merge_texts);
+
+        prop::collection::vec(line, 0..4).prop_map(|mut ast| {
+            // `str::lines` does not output a trailing empty line
+            while ast.last().is_some_and(Vec::is_empty) {
+                ast.pop();
+            }
+            ast
+        })
+    }
+
+    /// Any AST a user can build by hand: texts are not escaped, elements may have no tag.
+    fn hand_written_ast() -> impl Strategy<Value = Vec<Vec<Node>>> {
+        let node = prop_oneof![Just(String::new()), text()]
+            .prop_map(Node::Text)
+            .prop_recursive(4, 32, 4, |inner| {
+                (tags(0), prop::collection::vec(inner, 0..4))
+                    .prop_map(|(tags, children)| Node::Element(tags, children))
+            });
+        prop::collection::vec(prop::collection::vec(node, 0..6), 0..4)
+    }
+
+    proptest! {
+        #[test]
+        fn test_print_then_parse_round_trip(ast in canonical_ast()) {
+            let ast = items(&ast);
+            let source = print(&ast);
+            prop_assert_eq!(parse(&source).unwrap(), ast);
+        }
+
+        #[test]
+        fn test_print_hand_written_is_valid(ast in hand_written_ast()) {
+            let source = print(&items(&ast));
+            let reparsed = parse(&source).unwrap();
+            // A trailing empty line is dropped by parser, like `str::lines`
+            prop_assert_eq!(print(&reparsed), source.strip_suffix('\n').unwrap_or(&source));
+        }
+    }
+
+    #[test]
+    fn test_print_escape_hand_written_item() {
+        let line = vec![
+            Item::PlainText("1 < 2"),
+            Item::Element(vec![], vec![Item::PlainText("x")]),
+        ];
+        assert_eq!(print_line(&line), "1 \\< 2x");
+    }
+
+    #[cfg(feature = "ratatui")]
+    #[test]
+    fn test_format_keeps_output() {
+        use crate::{compile, generator::RatatuiTextGenerator};
+
+        let mut convertor = <crate::generator::ratatui::RatatuiTagConvertor>::default();
+
+        for (source, expected) in [
+            ("<fg:red,mod:i x>", "<red,i x>"),
+            ("<:b x>", "<b x>"),
+            ("<b <i <u x>>>", "<b,i,u x>"),
+            ("<red,green,red x>", "<green,red x>"),
+            ("<b x><b y>", "<b xy>"),
+            ("a<b >b<i <u >>\\\\", "ab\\\\"),
+            ("<bg:blue <red one> two>", "<bg:blue <red one> two>"),
+            ("<unknown,fg:unknown x>", "<unknown,fg:unknown x>"),
+        ] {
+            let formatted = super::format(source, &mut convertor).unwrap();
+            assert_eq!(formatted, expected);
+
+            let rendered = |s| {
+                compile::<RatatuiTextGenerator>(s)
+                    .unwrap()
+                    .lines
+                    .into_iter()
+                    .flat_map(|line| line.spans)
+                    .flat_map(|span| {
+                        let style = span.style;
+                        span.content
+                            .chars()
+                            .map(move |c| (c, style))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(rendered(source), rendered(&formatted));
+        }
+    }
+
+    #[cfg(feature = "ratatui")]
+    #[test]
+    fn test_format_custom_tag_is_kept() {
+        use ratatui_core::style::Style;
+
+        let mut convertor =
+            crate::generator::ratatui::RatatuiTagConvertor::new(|tag: &str| match tag {
+                "green" => Some(Style::default()),
+                _ => None,
+            });
+
+        assert_eq!(
+            super::format("<fg:green x>", &mut convertor),
+            Ok("<fg:green x>".to_owned())
+        );
+    }
+}
@@ -0,0 +1,421 @@
+//! Markup templates with named placeholders, parsed once and rendered many times.
+
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec, vec::Vec};
+use core::{
+    borrow::Borrow,
+    fmt::{Debug, Display},
+};
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{
+    Error, Generator,
+    generator::TagConvertor,
+    parser::{self, Item, parse_line},
+};
+
+/// A value to fill into a slot of [`Template`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value<'a> {
+    /// Plain text, which is shown as is, even it contains markup syntax like `<` or `\`.
+    Text(&'a str),
+    /// Markup source, which is parsed at render time, so it can contain elements.
+    Markup(&'a str),
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(s: &'a str) -> Self {
+        Self::Text(s)
+    }
+}
+
+/// Types can be used as a value of slot.
+///
+/// Strings are used as [`Value::Text`], use [`Value`] itself for other kinds.
+pub trait AsValue {
+    /// Get the value.
+    fn as_value(&self) -> Value<'_>;
+}
+
+impl AsValue for Value<'_> {
+    fn as_value(&self) -> Value<'_> {
+        *self
+    }
+}
+
+impl AsValue for str {
+    fn as_value(&self) -> Value<'_> {
+        Value::Text(self)
+    }
+}
+
+impl AsValue for String {
+    fn as_value(&self) -> Value<'_> {
+        Value::Text(self)
+    }
+}
+
+impl AsValue for Cow<'_, str> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Text(self)
+    }
+}
+
+impl<T: AsValue + ?Sized> AsValue for &T {
+    fn as_value(&self) -> Value<'_> {
+        (**self).as_value()
+    }
+}
+
+/// Named values for render a [`Template`].
+///
+/// Implemented for references of maps and lists of name-value pairs, and closures.
+pub trait Values<'a> {
+    /// Get value by slot name.
+    fn get(&self, name: &str) -> Option<Value<'a>>;
+}
+
+#[cfg(feature = "std")]
+impl<'a, K, V, S> Values<'a> for &'a HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: AsValue,
+    S: BuildHasher,
+{
+    fn get(&self, name: &str) -> Option<Value<'a>> {
+        HashMap::get(self, name).map(AsValue::as_value)
+    }
+}
+
+impl<'a, K, V> Values<'a> for &'a BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: AsValue,
+{
+    fn get(&self, name: &str) -> Option<Value<'a>> {
+        BTreeMap::get(self, name).map(AsValue::as_value)
+    }
+}
+
+impl<'a, K, V> Values<'a> for &'a [(K, V)]
+where
+    K: Borrow<str>,
+    V: AsValue,
+{
+    fn get(&self, name: &str) -> Option<Value<'a>> {
+        self.iter()
+            .find(|(k, _)| k.borrow() == name)
+            .map(|(_, v)| v.as_value())
+    }
+}
+
+impl<'a, K, V, const N: usize> Values<'a> for &'a [(K, V); N]
+where
+    K: Borrow<str>,
+    V: AsValue,
+{
+    fn get(&self, name: &str) -> Option<Value<'a>> {
+        Values::get(&self.as_slice(), name)
+    }
+}
+
+impl<'a, F> Values<'a> for F
+where
+    F: Fn(&str) -> Option<Value<'a>>,
+{
+    fn get(&self, name: &str) -> Option<Value<'a>> {
+        self(name)
+    }
+}
+
+/// Error for render a [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError<'a, GE> {
+    /// There is no value for a slot.
+    MissingValue(&'a str),
+    /// A [`Value::Markup`] contains invalid syntax, or generator failed.
+    Compile(Error<'a, GE>),
+}
+
+impl<GE: Display> Display for RenderError<'_, GE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingValue(name) => write!(f, "missing value for slot {{{name}}}"),
+            Self::Compile(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<GE> std::error::Error for RenderError<'_, GE> where Self: Debug + Display {}
+
+impl<'a, GE> From<Error<'a, GE>> for RenderError<'a, GE> {
+    fn from(e: Error<'a, GE>) -> Self {
+        Self::Compile(e)
+    }
+}
+
+impl<'a, GE> From<parser::Error<'a>> for RenderError<'a, GE> {
+    fn from(e: parser::Error<'a>) -> Self {
+        Self::Compile(Error::Parse(e))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node<'t> {
+    Text(&'t str),
+    Slot(&'t str),
+    Element(Vec<&'t str>, Vec<Node<'t>>),
+}
+
+fn is_slot_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Split plain text at slots, `{{` and `}}` are literal `{` and `}`.
+fn split_slots<'t>(mut text: &'t str, nodes: &mut Vec<Node<'t>>) {
+    while let Some(i) = text.find(['{', '}']) {
+        let after = &text[i..];
+
+        if after.starts_with("{{") || after.starts_with("}}") {
+            nodes.push(Node::Text(&text[..=i]));
+            text = &after[2..];
+            continue;
+        }
+
+        if let Some(name) = after
+            .strip_prefix('{')
+            .and_then(|s| s.split_once('}'))
+            .map(|(name, _)| name)
+            .filter(|name| is_slot_name(name))
+        {
+            if i > 0 {
+                nodes.push(Node::Text(&text[..i]));
+            }
+            nodes.push(Node::Slot(name));
+            text = &after[name.len() + 2..];
+            continue;
+        }
+
+        nodes.push(Node::Text(&text[..=i]));
+        text = &after[1..];
+    }
+
+    if !text.is_empty() {
+        nodes.push(Node::Text(text));
+    }
+}
+
+fn to_nodes(items: Vec<Item<'_>>) -> Vec<Node<'_>> {
+    let mut nodes = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Item::PlainText(t) => split_slots(t, &mut nodes),
+            Item::Element(tags, children) => nodes.push(Node::Element(tags, to_nodes(children))),
+        }
+    }
+    nodes
+}
+
+/// Push plain text as escaped text items, without copy it.
+fn push_text<'a>(mut text: &'a str, items: &mut Vec<Item<'a>>) {
+    // `<` and `>` are kept as is by unescape, only `\` need a escaped version.
+    while let Some(i) = text.find('\\') {
+        if i > 0 {
+            items.push(Item::PlainText(&text[..i]));
+        }
+        items.push(Item::PlainText("\\\\"));
+        text = &text[i + 1..];
+    }
+    if !text.is_empty() {
+        items.push(Item::PlainText(text));
+    }
+}
+
+fn fill<'a, GE>(
+    nodes: &[Node<'a>], values: &impl Values<'a>,
+) -> Result<Vec<Item<'a>>, RenderError<'a, GE>> {
+    let mut items = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match node {
+            Node::Text(t) => items.push(Item::PlainText(t)),
+            Node::Slot(name) => match values.get(name) {
+                Some(Value::Text(t)) => push_text(t, &mut items),
+                Some(Value::Markup(m)) => items.extend(parse_line(0, m)?),
+                None => return Err(RenderError::MissingValue(name)),
+            },
+            Node::Element(tags, children) => {
+                items.push(Item::Element(tags.clone(), fill(children, values)?));
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Markup source with `{name}` slots, which is parsed only once.
+///
+/// Slots are only recognized in plain text, not in tags. A slot name contains ASCII letters,
+/// digits, `_` and `-`. Use `{{` and `}}` for literal `{` and `}`, other braces are kept as is.
+///
+/// Rendering fills values into slots, keeping styles of elements around them, then generates output
+/// without parsing the template again. Values are inserted into the same line, line breaks in them
+/// do not start new lines.
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "ansi")] {
+/// use tui_markup::{generator::ANSIStringsGenerator, template::Template};
+///
+/// let template = Template::new("<b {name}>: <green {status}>").unwrap();
+///
+/// let text = template
+///     .render::<ANSIStringsGenerator, _>(&[("name", "<db>"), ("status", "ok")])
+///     .unwrap();
+/// assert_eq!(text.to_string(), "\x1b[1m<db>\x1b[0m: \x1b[32mok\x1b[0m");
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<'t> {
+    lines: Vec<Vec<Node<'t>>>,
+}
+
+impl<'t> Template<'t> {
+    /// Parse markup source into template.
+    ///
+    /// ## Errors
+    ///
+    /// If source contains invalid syntax.
+    pub fn new(source: &'t str) -> Result<Self, parser::Error<'t>> {
+        let lines = parser::parse(source)?.into_iter().map(to_nodes).collect();
+        Ok(Self { lines })
+    }
+
+    /// Names of all slots, in the order they appear. A name used many times appears many times.
+    pub fn slots(&self) -> impl Iterator<Item = &'t str> + '_ {
+        fn collect<'t>(nodes: &[Node<'t>], names: &mut Vec<&'t str>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Slot(name) => names.push(name),
+                    Node::Element(_, children) => collect(children, names),
+                }
+            }
+        }
+
+        let mut names = vec![];
+        for line in &self.lines {
+            collect(line, &mut names);
+        }
+        names.into_iter()
+    }
+
+    /// Fill values into slots, get the AST.
+    ///
+    /// ## Errors
+    ///
+    /// If there is no value for a slot, or a markup value contains invalid syntax.
+    pub fn fill<'a, GE>(
+        &self, values: impl Values<'a>,
+    ) -> Result<Vec<Vec<Item<'a>>>, RenderError<'a, GE>>
+    where
+        't: 'a,
+    {
+        self.lines.iter().map(|line| fill(line, &values)).collect()
+    }
+
+    /// Render template with values, using the default configure of a generator type.
+    ///
+    /// ## Errors
+    ///
+    /// If there is no value for a slot, a markup value contains invalid syntax, or generator
+    /// failed.
+    pub fn render<'a, G, V>(&self, values: V) -> Result<G::Output, RenderError<'a, G::Err>>
+    where
+        't: 'a,
+        G: Generator<'a> + Default,
+        V: Values<'a>,
+    {
+        self.render_with(G::default(), values)
+    }
+
+    /// Render template with values, using the provided generator.
+    ///
+    /// ## Errors
+    ///
+    /// If there is no value for a slot, a markup value contains invalid syntax, or generator
+    /// failed.
+    pub fn render_with<'a, G, V>(
+        &self, mut generator: G, values: V,
+    ) -> Result<G::Output, RenderError<'a, G::Err>>
+    where
+        't: 'a,
+        G: Generator<'a>,
+        V: Values<'a>,
+    {
+        let ast = self.fill(values)?;
+        let ir = generator.convertor().convert_ast(ast);
+        generator
+            .generate(ir)
+            .map_err(|e| RenderError::Compile(e.into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::collections::BTreeMap;
+
+    use super::{RenderError, Template, Value};
+    use crate::{generator::helper::GeneratorInfallible, parser::parse, printer::print};
+
+    type Result<'a> = std::result::Result<
+        Vec<Vec<crate::parser::Item<'a>>>,
+        RenderError<'a, GeneratorInfallible>,
+    >;
+
+    #[test]
+    fn test_slots() {
+        let template = Template::new("<b {a}>{{b}} {c}}<i {-_1}{}{ d}>\n{a}").unwrap();
+        assert_eq!(template.slots().collect::<Vec<_>>(), vec![
+            "a", "c", "-_1", "a"
+        ]);
+    }
+
+    #[test]
+    fn test_fill_keeps_style() {
+        let template = Template::new("<b {name}>: <green,u {status}!>").unwrap();
+        let values = BTreeMap::from([("name", "a"), ("status", "ok")]);
+        let result: Result<'_> = template.fill(&values);
+        assert_eq!(print(&result.unwrap()), "<b a>: <green,u ok!>");
+    }
+
+    #[test]
+    fn test_text_value_is_escaped() {
+        let template = Template::new("<b {v}>").unwrap();
+        let result: Result<'_> = template.fill(&[("v", "<i a\\b>")]);
+        assert_eq!(print(&result.unwrap()), "<b \\<i a\\\\b\\>>");
+    }
+
+    #[test]
+    fn test_markup_value() {
+        let template = Template::new("<b {v}>").unwrap();
+        let result: Result<'_> = template.fill(|_: &str| Some(Value::Markup("<i x>")));
+        assert_eq!(result.unwrap(), parse("<b <i x>>").unwrap());
+
+        let result: Result<'_> = template.fill(|_: &str| Some(Value::Markup("<i x")));
+        assert!(matches!(result, Err(RenderError::Compile(_))));
+    }
+
+    #[test]
+    fn test_missing_value() {
+        let template = Template::new("{a} {b}").unwrap();
+        let result: Result<'_> = template.fill(&[("a", "1")]);
+        assert_eq!(result, Err(RenderError::MissingValue("b")));
+    }
+}
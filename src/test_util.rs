@@ -0,0 +1,42 @@
+//! Helpers shared by tests of different modules.
+
+use crate::{generator::TagConvertor, parser::hex_rgb};
+
+/// Color type of [`Convertor`], the depth of the color.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Color {
+    Named,
+    Indexed,
+    Rgb,
+}
+
+/// Convertor of a small tag set, in the color syntax of builtin generators.
+///
+/// - Colors: `red`, `gray+`, any xterm-256 index and hex rgb.
+/// - Modifiers: `b`, `h`, `sb`, `rb`, `r`.
+/// - Custom tags: `keyboard`, `internal`, and `red`, which is also a color.
+pub(crate) struct Convertor;
+
+impl TagConvertor<'_> for Convertor {
+    type Color = Color;
+    type Custom = ();
+    type Modifier = ();
+
+    fn parse_color(&mut self, s: &str) -> Option<Color> {
+        if hex_rgb(s).is_some() {
+            Some(Color::Rgb)
+        } else if s.parse::<u8>().is_ok() {
+            Some(Color::Indexed)
+        } else {
+            matches!(s, "red" | "gray+").then_some(Color::Named)
+        }
+    }
+
+    fn parse_modifier(&mut self, s: &str) -> Option<()> {
+        matches!(s, "b" | "h" | "sb" | "rb" | "r").then_some(())
+    }
+
+    fn parse_custom_tag(&mut self, s: &str) -> Option<()> {
+        matches!(s, "keyboard" | "internal" | "red").then_some(())
+    }
+}
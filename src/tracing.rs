@@ -0,0 +1,243 @@
+//! Integration with [tracing-subscriber] crate, for render markup in log messages.
+//!
+//! [tracing-subscriber]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/
+
+use std::fmt::{Debug, Write};
+
+use anstyle::Style;
+use tracing_core::Field;
+use tracing_subscriber::{
+    field::{RecordFields, Visit},
+    fmt::{FormatFields, format::Writer},
+};
+
+use crate::{
+    compile_with,
+    generator::{
+        ANSIStringsGenerator,
+        helper::{CustomTagParser, NoopCustomTagParser, Sanitize, escape, sanitize},
+    },
+};
+
+/// A [`FormatFields`] implementation which treats the log message as tui markup.
+///
+/// The message is compiled by [`ANSIStringsGenerator`], so it supports the same tags, see
+/// [docs/ansi-tags.ebnf].
+///
+/// - When the writer supports ANSI escapes, the styled result is written.
+/// - When it does not (for example, `with_ansi(false)` or logging into a file), only the plain text
+///   is written, without any markup tag.
+/// - Other fields are written after the message as `key=value`, their values are escaped so they
+///   are always shown as-is.
+/// - If the message is invalid markup, it's written as-is with control characters replaced, the log
+///   line will never be dropped.
+///
+/// ## Interpolated values are not escaped
+///
+/// Unlike other fields, values interpolated into the message by format arguments are **not**
+/// escaped. The message reaches the subscriber as already formatted text, so they can't be told
+/// apart from the format string, and a value like `x> <red y` changes the style of the log line.
+/// Wrap untrusted values with [`Escaped`][crate::generator::helper::Escaped], or record them as
+/// fields.
+///
+/// ## Example
+///
+/// ```
+/// use tui_markup::{generator::helper::Escaped, tracing::MarkupFields};
+///
+/// tracing_subscriber::fmt()
+///     .fmt_fields(MarkupFields::default())
+///     .init();
+///
+/// tracing::info!("connected to <b,cyan {}>", "localhost");
+///
+/// let user = "x> <red y";
+/// tracing::info!("login by <b {}>", Escaped(user));
+/// tracing::info!(user, "login");
+/// ```
+///
+/// [docs/ansi-tags.ebnf]: https://github.com/7sDream/tui-markup/blob/master/docs/ansi-tags.ebnf
+#[derive(Debug, Clone)]
+pub struct MarkupFields<P = NoopCustomTagParser<Style>> {
+    custom_tag_parser: Option<P>,
+}
+
+impl Default for MarkupFields {
+    fn default() -> Self {
+        Self {
+            custom_tag_parser: None,
+        }
+    }
+}
+
+impl<P> MarkupFields<P> {
+    /// Create a new fields formatter with custom tag parser.
+    ///
+    /// The parser will be cloned for every formatted event.
+    pub fn new(p: P) -> Self {
+        Self {
+            custom_tag_parser: Some(p),
+        }
+    }
+}
+
+impl<P> MarkupFields<P>
+where
+    P: CustomTagParser<Output = Style> + Clone,
+{
+    fn generator(&self) -> ANSIStringsGenerator<P> {
+        match &self.custom_tag_parser {
+            Some(p) => ANSIStringsGenerator::new(p.clone()),
+            None => ANSIStringsGenerator::default(),
+        }
+    }
+}
+
+impl<'writer, P> FormatFields<'writer> for MarkupFields<P>
+where
+    P: CustomTagParser<Output = Style> + Clone,
+{
+    fn format_fields<R: RecordFields>(
+        &self, mut writer: Writer<'writer>, fields: R,
+    ) -> std::fmt::Result {
+        let mut visitor = Visitor::default();
+        fields.record(&mut visitor);
+
+        let markup = visitor.render(true);
+        match compile_with(&markup, self.generator()) {
+            Ok(text) if writer.has_ansi_escapes() => write!(writer, "{text}"),
+            Ok(text) => text
+                .spans()
+                .iter()
+                .try_for_each(|span| writer.write_str(span.text())),
+            // The raw message may contain escape sequences from interpolated values too
+            Err(_) => sanitize(&visitor.render(false), Sanitize::default())
+                .try_for_each(|s| writer.write_str(s)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Visitor {
+    message: Option<String>,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Visitor {
+    fn render(&self, markup: bool) -> String {
+        let mut result = self.message.clone().unwrap_or_default();
+        for (name, value) in &self.fields {
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            // Write into String never fails.
+            let _ = if markup {
+                write!(result, "<i {}>={}", escape(name), escape(value))
+            } else {
+                write!(result, "{name}={value}")
+            };
+        }
+        result
+    }
+}
+
+impl Visit for Visitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        match field.name() {
+            "message" => self.message = Some(format!("{value:?}")),
+            name if name.starts_with("log.") => {}
+            name => self.fields.push((name, format!("{value:?}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::MarkupFields;
+    use crate::generator::helper::Escaped;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn capture(ansi: bool, f: impl FnOnce()) -> String {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .fmt_fields(MarkupFields::default())
+            .with_writer(buffer.clone())
+            .with_ansi(ansi)
+            .without_time()
+            .with_level(false)
+            .with_target(false)
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, f);
+
+        String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn test_plain_output() {
+        let output = capture(false, || tracing::info!("connected to <b,cyan {}>", "host"));
+        assert_eq!(output, "connected to host\n");
+    }
+
+    #[test]
+    fn test_ansi_output() {
+        let output = capture(true, || tracing::info!("<b bold>"));
+        assert!(output.contains("\u{1b}[1mbold\u{1b}[0m"));
+    }
+
+    #[test]
+    fn test_fields_are_escaped() {
+        let output = capture(false, || tracing::info!(user = "<b x>", "login"));
+        assert_eq!(output, "login user=\"<b x>\"\n");
+    }
+
+    #[test]
+    fn test_interpolated_values() {
+        let user = "x> <i y";
+        let output = capture(false, || tracing::info!("<b {}>", user));
+        assert_eq!(output, "x y\n");
+
+        let output = capture(false, || tracing::info!("<b {}>", Escaped(user)));
+        assert_eq!(output, "x> <i y\n");
+    }
+
+    #[test]
+    fn test_invalid_markup_fallback() {
+        let output = capture(false, || tracing::info!(code = 1, "1 < 2"));
+        assert_eq!(output, "1 < 2 code=1\n");
+    }
+
+    #[test]
+    fn test_invalid_markup_fallback_is_sanitized() {
+        let output = capture(false, || tracing::info!("{}", "<\x1b[2J"));
+        assert_eq!(output, "<^[[2J\n");
+
+        let output = capture(true, || tracing::info!("{} <", "\x1b]52;c;eA==\x07"));
+        assert!(!output.contains("\x1b]"));
+        assert!(output.contains("^[]52;c;eA==^G <"));
+    }
+}